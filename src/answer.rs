@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Int(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        // Anything too big for an i64 is still a valid answer, it just has to be kept as text
+        i64::try_from(value).map(Answer::Int).unwrap_or_else(|_| Answer::Str(value.to_string()))
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        i64::try_from(value).map(Answer::Int).unwrap_or_else(|_| Answer::Str(value.to_string()))
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::from(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Str(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+/// The answers a solver found, indexed by part number (1 or 2).
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    parts: [Option<Answer>; 2],
+}
+
+impl Answers {
+    pub fn set(&mut self, part: u32, answer: impl Into<Answer>) {
+        self.parts[part as usize - 1] = Some(answer.into());
+    }
+
+    /// Iterate over the parts that have an answer, as `(part, answer)`
+    pub fn iter(&self) -> impl Iterator<Item=(u32, &Answer)> {
+        self.parts.iter().enumerate().filter_map(|(idx, a)| a.as_ref().map(|a| (idx as u32 + 1, a)))
+    }
+}
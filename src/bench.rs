@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use color_eyre::Report;
+use tracing::debug;
+
+use crate::solver::{Solver, Timings};
+
+#[derive(Debug)]
+struct Stats {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    stddev: Duration,
+}
+
+impl Stats {
+    fn from_samples(samples: &mut [Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None
+        }
+        samples.sort();
+
+        let n = samples.len() as f64;
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        let mean = samples.iter().map(|d| d.as_secs_f64()).sum::<f64>() / n;
+        let variance = samples.iter().map(|d| (d.as_secs_f64() - mean).powi(2)).sum::<f64>() / n;
+
+        Some(Stats {
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Run `solver` `warmup` times untimed and then `runs` times, and print min/median/mean/stddev for
+/// each phase as CSV (times in nanoseconds).
+pub fn bench(solver: &dyn Solver, input: &str, part: Option<u32>, warmup: u32, runs: u32) -> Result<(), Report> {
+    for _ in 0..warmup {
+        solver.solve_timed(input.to_string(), part)?;
+    }
+
+    let mut samples: Vec<Timings> = Vec::with_capacity(runs as usize);
+    for run in 0..runs {
+        let (_, timings) = solver.solve_timed(input.to_string(), part)?;
+        debug!(run, ?timings);
+        samples.push(timings);
+    }
+
+    let phases: [(&str, Vec<Duration>); 3] = [
+        ("parse", samples.iter().map(|t| t.parse).collect()),
+        ("part1", samples.iter().filter_map(|t| t.part1).collect()),
+        ("part2", samples.iter().filter_map(|t| t.part2).collect()),
+    ];
+
+    println!("day,phase,runs,min_ns,median_ns,mean_ns,stddev_ns");
+    for (phase, mut durations) in phases {
+        if let Some(stats) = Stats::from_samples(&mut durations) {
+            println!("{},{},{},{},{},{},{}",
+                solver.day(), phase, durations.len(),
+                stats.min.as_nanos(), stats.median.as_nanos(), stats.mean.as_nanos(), stats.stddev.as_nanos()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where the sequence `start, step(start), step(step(start)), ...` starts repeating: the state
+/// after `mu` steps is the first one that comes round again, every `lambda` steps from then on
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The step before `mu + lambda` that ends in the same state as `n` steps
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.mu { n } else { self.mu + (n - self.mu) % self.lambda }
+    }
+
+    /// The state after `n` steps from `start`, taking at most `mu + lambda` of them
+    pub fn state_after<S: Clone>(&self, start: &S, n: usize, step: impl FnMut(&S) -> S) -> S {
+        iterate(start, self.equivalent_step(n), step)
+    }
+}
+
+/// The state after `n` steps from `start`
+pub fn iterate<S: Clone>(start: &S, n: usize, mut step: impl FnMut(&S) -> S) -> S {
+    let mut state = start.clone();
+    for _ in 0..n {
+        state = step(&state);
+    }
+    state
+}
+
+/// Find the cycle by remembering every state seen. Takes `mu + lambda` steps, but keeps all those
+/// states. Doesn't return if the states never repeat.
+pub fn find_hashed<S: Clone + Eq + Hash>(start: &S, step: impl FnMut(&S) -> S) -> Cycle {
+    find_hashed_by(start, step, S::clone)
+}
+
+/// Like `find_hashed`, remembering a fingerprint of each state rather than the state itself. States
+/// are taken to be the same if their fingerprints are.
+pub fn find_hashed_by<S: Clone, K: Eq + Hash>(start: &S, mut step: impl FnMut(&S) -> S, fingerprint: impl Fn(&S) -> K) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start.clone();
+    for steps in 0.. {
+        if let Some(mu) = seen.insert(fingerprint(&state), steps) {
+            return Cycle { mu, lambda: steps - mu }
+        }
+        state = step(&state);
+    }
+    unreachable!("ran out of steps looking for a cycle")
+}
+
+/// Find the cycle with Brent's algorithm, which only ever holds a couple of states but takes
+/// up to about three times as many steps as `find_hashed`. Doesn't return if the states never
+/// repeat.
+pub fn find_brent<S: Clone + Eq>(start: &S, step: impl FnMut(&S) -> S) -> Cycle {
+    brent(start, step, |a, b| a == b)
+}
+
+/// Like `find_brent`, taking states to be the same if their fingerprints are
+pub fn find_brent_by<S: Clone, K: Eq>(start: &S, step: impl FnMut(&S) -> S, fingerprint: impl Fn(&S) -> K) -> Cycle {
+    brent(start, step, |a, b| fingerprint(a) == fingerprint(b))
+}
+
+fn brent<S: Clone>(start: &S, mut step: impl FnMut(&S) -> S, same: impl Fn(&S, &S) -> bool) -> Cycle {
+    // Find lambda: the hare runs ahead, and the tortoise teleports to it at every power of two
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+    while !same(&tortoise, &hare) {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    // Find mu: with the hare lambda steps ahead, they meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = iterate(start, lambda, &mut step);
+    let mut mu = 0;
+    while !same(&tortoise, &hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    Cycle { mu, lambda }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quadratic(a: u64, c: u64, m: u64) -> impl Fn(&u64) -> u64 {
+        move |x| (x * x * a + c) % m
+    }
+
+    #[test]
+    fn brent_agrees_with_hashed() {
+        for (start, a, c, m) in [(0, 1, 1, 255), (3, 7, 5, 1000), (2, 1, 0, 97), (5, 3, 11, 4099), (1, 1, 0, 2)] {
+            let step = quadratic(a, c, m);
+            assert_eq!(find_brent(&start, &step), find_hashed(&start, &step), "x -> ({a}x² + {c}) % {m} from {start}");
+        }
+    }
+
+    #[test]
+    fn cycle_of_a_known_sequence() {
+        // 0, 1, 2, 3, 4, 5, 3, 4, 5, ...
+        let step = |&x: &u32| if x == 5 { 3 } else { x + 1 };
+        assert_eq!(find_hashed(&0, step), Cycle { mu: 3, lambda: 3 });
+        assert_eq!(find_brent(&0, step), Cycle { mu: 3, lambda: 3 });
+        // A fixed point from the start
+        assert_eq!(find_brent(&7, |&x: &u32| x), Cycle { mu: 0, lambda: 1 });
+        assert_eq!(find_hashed(&7, |&x: &u32| x), Cycle { mu: 0, lambda: 1 });
+    }
+
+    #[test]
+    fn cycle_by_fingerprint() {
+        // The step counter makes every state different, but the fingerprint ignores it
+        let step = |&(x, n): &(u64, u64)| ((x * x + 1) % 255, n + 1);
+        let expected = find_hashed(&0, quadratic(1, 1, 255));
+        assert_eq!(find_hashed_by(&(0, 0), step, |&(x, _)| x), expected);
+        assert_eq!(find_brent_by(&(0, 0), step, |&(x, _)| x), expected);
+    }
+
+    #[test]
+    fn state_after_matches_iterating() {
+        let step = quadratic(7, 5, 1000);
+        let cycle = find_brent(&3, &step);
+        assert!(cycle.mu > 0);
+        for n in (0..cycle.mu).chain([cycle.mu, cycle.mu + 1, cycle.mu + cycle.lambda, 5 * cycle.mu + 3 * cycle.lambda + 2]) {
+            assert!(cycle.equivalent_step(n) < cycle.mu + cycle.lambda);
+            assert_eq!(cycle.state_after(&3, n, &step), iterate(&3, n, &step), "after {n} steps");
+        }
+    }
+}
@@ -1,11 +1,38 @@
 use color_eyre::Report;
-use tracing::info;
+use tracing::debug;
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+use crate::solver::{Example, PartResult, Puzzle};
 
-    info!(day=1, part=1, answer=);
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: include_str!("../examples/day00.txt"),
+        part1: None,
+        part2: None,
+    },
+];
 
-    //info!(day=1, part=2, answer=);
+pub struct Day0;
 
-    Ok(())
+impl Puzzle for Day0 {
+    const DAY: u32 = 0;
+    const TITLE: &'static str = "";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(lines: &Self::Input) -> PartResult {
+        debug!(?lines);
+        //info!(day=0, part=1, answer=);
+        Ok(None)
+    }
+
+    fn part2(lines: &Self::Input) -> PartResult {
+        debug!(lines=lines.len());
+        //info!(day=0, part=2, answer=);
+        Ok(None)
+    }
 }
@@ -1,17 +1,18 @@
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::one_of;
 use nom::combinator::{map, rest, value};
-use nom::IResult;
 use nom::sequence::tuple;
 use tracing::{debug, info};
 
-fn decimal_digit(input: &str) -> IResult<&str, u8> {
+use crate::parse::{IResult, one_of, tag};
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub fn decimal_digit(input: &str) -> IResult<&str, u8> {
     map(one_of("0123456789"), |ch: char| ch as u8 - 0x30)(input)
 }
 
-fn text_digit(input: &str) -> IResult<&str, u8> {
+pub fn text_digit(input: &str) -> IResult<&str, u8> {
     alt((
         value(1, tag("one")),
         value(2, tag("two")),
@@ -25,15 +26,15 @@ fn text_digit(input: &str) -> IResult<&str, u8> {
     ))(input)
 }
 
-fn starts_with_decimal_digit(input: &str) -> IResult<&str, u8> {
+pub fn starts_with_decimal_digit(input: &str) -> IResult<&str, u8> {
     tuple((decimal_digit, rest))(input).map(|(rest, (digit, _))| (rest, digit))
 }
 
-fn starts_with_any_digit(input: &str) -> IResult<&str, u8> {
+pub fn starts_with_any_digit(input: &str) -> IResult<&str, u8> {
     tuple((alt((decimal_digit, text_digit)), rest))(input).map(|(rest, (digit, _))| (rest, digit))
 }
 
-fn first(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8> {
+pub fn first(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8> {
     for i in 0..=input.len() {
         if let Ok((_, digit)) = parser(&input[i..input.len()]) {
             return Some(digit)
@@ -43,7 +44,7 @@ fn first(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8>
     None
 }
 
-fn last(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8> {
+pub fn last(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8> {
     for i in 0..=input.len() {
         if let Ok((_, digit)) = parser(&input[input.len() - i..input.len()]) {
             return Some(digit)
@@ -53,23 +54,68 @@ fn last(input: &str, parser: impl Fn(&str) -> IResult<&str, u8>) -> Option<u8> {
     None
 }
 
-fn part1_value(input: &str) -> u8 {
+pub fn part1_value(input: &str) -> u8 {
     first(input, starts_with_decimal_digit).unwrap_or(0) * 10 + last(input, starts_with_decimal_digit).unwrap_or(0)
 }
 
-fn part2_value(input: &str) -> u8 {
-    first(input, starts_with_any_digit).unwrap() * 10 + last(input, starts_with_any_digit).unwrap()
+pub fn part2_value(input: &str) -> Option<u8> {
+    Some(first(input, starts_with_any_digit)? * 10 + last(input, starts_with_any_digit)?)
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+",
+        part1: Some("142"),
+        part2: None,
+    },
+    Example {
+        input: "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+",
+        part1: None,
+        part2: Some("281"),
+    },
+];
+
+pub struct Day1;
 
-    let part1_sum: i64 = input.lines().map(|line| part1_value(line) as i64).sum();
-    
-    info!(day=1, part=1, answer=part1_sum);
+impl Puzzle for Day1 {
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
 
-    let part2_sum: i64 = input.lines().map(|line| part2_value(line) as i64).sum();
+    type Input = Vec<String>;
 
-    info!(day=1, part=2, answer=part2_sum);
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
 
-    Ok(())
+    fn part1(lines: &Self::Input) -> PartResult {
+        let part1_sum: i64 = lines.iter().map(|line| part1_value(line) as i64).sum();
+
+        info!(day=1, part=1, answer=part1_sum);
+        Ok(Some(part1_sum.into()))
+    }
+
+    fn part2(lines: &Self::Input) -> PartResult {
+        let mut part2_sum: i64 = 0;
+        for (line_num, line) in lines.iter().enumerate() {
+            let value = part2_value(line).ok_or_else(|| eyre!("line {}: no digits in {line:?}", line_num + 1))?;
+            part2_sum += value as i64;
+        }
+
+        info!(day=1, part=2, answer=part2_sum);
+        Ok(Some(part2_sum.into()))
+    }
 }
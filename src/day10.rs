@@ -1,51 +1,47 @@
-use color_eyre::owo_colors::OwoColorize;
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
 use tracing::{info, warn};
 
-const NORTH_SOUTH: u8 = b'|';
-const EAST_WEST: u8 = b'-';
-const NORTH_EAST: u8 = b'L';
-const NORTH_WEST: u8 = b'J';
-const SOUTH_EAST: u8 = b'F';
-const SOUTH_WEST: u8 = b'7';
-const GROUND: u8 = b'.';
-const START: u8 = b'S';
-
-fn find_coords<T>(map: &[Vec<T>], pred: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
-    for (row_num, row) in map.iter().enumerate() {
-        for (col_num, val) in row.iter().enumerate() {
-            if pred(val) {
-                return Some((row_num, col_num))
-            }
-        }
-    }
-    None
-}
-
-fn start_to_pipe(map: &mut Vec<Vec<u8>>) {
-    let (row, col) = find_coords(map, |&c| c == START).unwrap();
-    let has_north = row > 0 && [NORTH_SOUTH, SOUTH_EAST, SOUTH_WEST].contains(&map[row-1][col]);
-    let has_south = row + 1 < map.len() && [NORTH_SOUTH, NORTH_EAST, NORTH_WEST].contains(&map[row+1][col]);
-    let has_west = col > 0 && [EAST_WEST, NORTH_EAST, SOUTH_EAST].contains(&map[row][col-1]);
-    let has_east = col + 1 < map[0].len() && [EAST_WEST, NORTH_WEST, SOUTH_WEST].contains(&map[row][col+1]);
-    map[row][col] = match (has_north, has_south, has_west, has_east) {
+use crate::grid::{Grid, Pos};
+use crate::render::{self, Canvas, Cell, Color, Style};
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub const NORTH_SOUTH: u8 = b'|';
+pub const EAST_WEST: u8 = b'-';
+pub const NORTH_EAST: u8 = b'L';
+pub const NORTH_WEST: u8 = b'J';
+pub const SOUTH_EAST: u8 = b'F';
+pub const SOUTH_WEST: u8 = b'7';
+pub const GROUND: u8 = b'.';
+pub const START: u8 = b'S';
+
+pub fn start_to_pipe(map: &mut Grid<u8>) -> Result<(), Report> {
+    let (row, col) = map.find(|&c| c == START).ok_or_else(|| eyre!("no start 'S' in the map"))?;
+    let connects = |d, pipes: [u8; 3]| map.offset((row, col), d).is_some_and(|pos| pipes.contains(&map[pos]));
+    let has_north = connects((-1, 0), [NORTH_SOUTH, SOUTH_EAST, SOUTH_WEST]);
+    let has_south = connects((1, 0), [NORTH_SOUTH, NORTH_EAST, NORTH_WEST]);
+    let has_west = connects((0, -1), [EAST_WEST, NORTH_EAST, SOUTH_EAST]);
+    let has_east = connects((0, 1), [EAST_WEST, NORTH_WEST, SOUTH_WEST]);
+    map[(row, col)] = match (has_north, has_south, has_west, has_east) {
         (true, true, false, false) => NORTH_SOUTH,
         (true, false, true, false) => NORTH_WEST,
         (true, false, false, true) => NORTH_EAST,
         (false, true, true, false) => SOUTH_WEST,
         (false, true, false, true) => SOUTH_EAST,
         (false, false, true, true) => EAST_WEST,
-        _ => panic!("can't convert {:?} to pipe", (has_north, has_south, has_east, has_west))
+        _ => return Err(eyre!("start at line {}, column {} doesn't connect to exactly two pipes", row + 1, col + 1))
     };
+    Ok(())
 }
 
-fn offsets(map: &Vec<Vec<u8>>, (row, col): (usize, usize)) -> Vec<(usize, usize)> {
-    let mut offsets = Vec::new();
-    let ch_type = map[row][col];
-    if (ch_type == NORTH_SOUTH || ch_type == NORTH_WEST || ch_type == NORTH_EAST) && row > 0 { offsets.push((row - 1, col)) }
-    if (ch_type == NORTH_SOUTH || ch_type == SOUTH_WEST || ch_type == SOUTH_EAST) && row + 1 < map.len() { offsets.push((row + 1, col)) }
-    if (ch_type == EAST_WEST || ch_type == NORTH_WEST || ch_type == SOUTH_WEST) && col > 0 { offsets.push((row, col - 1)) }
-    if (ch_type == EAST_WEST || ch_type == NORTH_EAST || ch_type == SOUTH_EAST) && col + 1 < map[0].len() { offsets.push((row, col + 1)) }
+pub fn offsets(map: &Grid<u8>, (row, col): Pos) -> Vec<Pos> {
+    let ch_type = map[(row, col)];
+    let mut directions = Vec::new();
+    if [NORTH_SOUTH, NORTH_WEST, NORTH_EAST].contains(&ch_type) { directions.push((-1, 0)) }
+    if [NORTH_SOUTH, SOUTH_WEST, SOUTH_EAST].contains(&ch_type) { directions.push((1, 0)) }
+    if [EAST_WEST, NORTH_WEST, SOUTH_WEST].contains(&ch_type) { directions.push((0, -1)) }
+    if [EAST_WEST, NORTH_EAST, SOUTH_EAST].contains(&ch_type) { directions.push((0, 1)) }
+    let offsets: Vec<Pos> = directions.into_iter().filter_map(|d| map.offset((row, col), d)).collect();
 
     if offsets.len() == 1 || offsets.len() == 3 {
         warn!(char=?ch_type as char, row, col, "weird offsets len");
@@ -53,23 +49,23 @@ fn offsets(map: &Vec<Vec<u8>>, (row, col): (usize, usize)) -> Vec<(usize, usize)
     offsets
 }
 
-fn show(map: &[Vec<u8>], steps: &[Vec<Option<i32>>]) {
-    for (map_row, steps_row) in map.iter().zip(steps.iter()) {
-        for (map_cell, steps_cell) in map_row.iter().zip(steps_row.iter()) {
-            match steps_cell {
-                Some(s) => print!("{}", s.bright_yellow().on_blue()),
-                None => print!("{}", (*map_cell as char).bright_black())
-            }
-        }
-        println!();
-    }
+fn steps_canvas(map: &Grid<u8>, steps: &Grid<Option<usize>>) -> Canvas {
+    let rows = map.rows().zip(steps.rows()).map(|(map_row, steps_row)|
+        map_row.iter().zip(steps_row.iter()).map(|(map_cell, steps_cell)| match steps_cell {
+            Some(s) => Cell::new(s, Style::fg(Color::BrightYellow).on(Color::Blue)),
+            None => Cell::new(*map_cell as char, Style::fg(Color::BrightBlack)),
+        }).collect()
+    ).collect();
+    Canvas { rows }
 }
 
-fn count_inner(clean_map: &[Vec<u8>]) -> i32 {
+pub fn count_inner(clean_map: &Grid<u8>) -> i32 {
     let mut count = 0;
     let mut east_corner = None;
-    for row in clean_map.iter() {
+    let mut canvas = Canvas::default();
+    for row in clean_map.rows() {
         let mut inside = false;
+        let mut canvas_row = Vec::new();
         for &map_cell in row.iter() {
             match map_cell {
                 GROUND => if inside { count += 1 },
@@ -91,59 +87,132 @@ fn count_inner(clean_map: &[Vec<u8>]) -> i32 {
                 }
                 _ => panic!("unexpected map cell {:?}", map_cell as char),
             }
-            if inside {
-                print!("{}", (map_cell as char).white().on_blue())
-            } else {
-                print!("{}", (map_cell as char).white().on_black())
+            if render::enabled() {
+                let bg = if inside { Color::Blue } else { Color::Black };
+                canvas_row.push(Cell::new(map_cell as char, Style::fg(Color::White).on(bg)));
             }
         }
-        println!();
+        canvas.rows.push(canvas_row);
     }
+    render::show("day 10 inside the loop", || canvas);
     count
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+#[derive(Debug)]
+pub struct Maze {
+    pub map: Grid<u8>,
+    pub steps: Grid<Option<usize>>,
+}
 
-    let mut map = Vec::new();
-    for line in input.lines() {
-        map.push(line.as_bytes().to_vec());
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF
+",
+        part1: Some("4"),
+        part2: None,
+    },
+    Example {
+        input: "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+",
+        part1: Some("8"),
+        part2: None,
+    },
+    Example {
+        input: "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+",
+        part1: None,
+        part2: Some("4"),
+    },
+    Example {
+        input: "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+",
+        part1: None,
+        part2: Some("8"),
+    },
+    Example {
+        input: "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+",
+        part1: None,
+        part2: Some("10"),
+    },
+];
+
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let mut map = Grid::parse(input, "|-LJ7F.S")?;
+
+        render::show("day 10 map", || steps_canvas(&map, &Grid::new(map.width(), map.height(), None)));
+
+        let start = map.find(|&c| c == START).ok_or_else(|| eyre!("no start 'S' in the map"))?;
+        start_to_pipe(&mut map)?;
+        let steps = map.flood_fill(start, |pos| offsets(&map, pos));
+
+        render::show("day 10 steps from the start", || steps_canvas(&map, &steps));
+
+        Ok(Maze { map, steps })
     }
 
-    let mut steps: Vec<Vec<Option<i32>>> = vec![
-        vec![None; map[0].len()];
-        map.len()
-    ];
-
-    show(&map, &steps);
-
-    let start = find_coords(&map, |&c| c == START).unwrap();
-    start_to_pipe(&mut map);
-    let mut to_check = vec![start];
-    steps[start.0][start.1] = Some(0);
-
-    while let Some((row, col)) = to_check.pop() {
-        let step = steps[row][col].unwrap();
-        for offset in offsets(&map, (row, col)) {
-            if steps[offset.0][offset.1].unwrap_or(i32::MAX) > step + 1 {
-                steps[offset.0][offset.1] = Some(step + 1);
-                to_check.push((offset.0, offset.1));
-            }
-        }
+    fn part1(maze: &Self::Input) -> PartResult {
+        let max_steps = maze.steps.cells().iter().flatten().max().copied().unwrap_or(0);
+        info!(day=10, part=1, answer=max_steps);
+        Ok(Some(max_steps.into()))
     }
 
-    show(&map, &steps);
-
-    let max_steps = steps.iter().flat_map(|s| s.iter().map(|s| s.unwrap_or(0)).max()).max().unwrap();
-    info!(day=1, part=1, answer=max_steps);
-
-    let clean_map: Vec<Vec<u8>> = map.iter().zip(steps.iter()).map(
-        |(row, steps_row)| row.iter().zip(steps_row.iter()).map(
-            |(ch, step)| if step.is_some() { *ch } else { GROUND }
-        ).collect()
-    ).collect();
-
-    let inner = count_inner(&clean_map);
-    info!(day=1, part=2, answer=inner);
+    fn part2(maze: &Self::Input) -> PartResult {
+        let mut clean_map = maze.map.clone();
+        for pos in maze.steps.find_all(|step| step.is_none()) {
+            clean_map[pos] = GROUND;
+        }
 
-    Ok(())
+        let inner = count_inner(&clean_map);
+        info!(day=10, part=2, answer=inner);
+        Ok(Some(inner.into()))
+    }
 }
@@ -1,71 +1,97 @@
 use color_eyre::Report;
 use tracing::{debug, info};
 
-const GALAXY: u8 = b'#';
-const SPACE: u8 = b'.';
+use crate::grid::Grid;
+use crate::solver::{Example, PartResult, Puzzle};
 
-const PART2_FACTOR: usize = 1000000;
+pub const GALAXY: u8 = b'#';
+pub const SPACE: u8 = b'.';
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+pub const PART2_FACTOR: usize = 1000000;
 
-    let mut map = Vec::new();
-    for line in input.lines() {
-        map.push(line.as_bytes().to_vec());
-    }
+#[derive(Debug)]
+pub struct Image {
+    pub galaxies: Vec<(usize, usize)>,
+    pub doubled_rows: Vec<usize>,
+    pub doubled_cols: Vec<usize>,
+}
 
-    let mut galaxies = Vec::new();
-    let mut doubled_rows = Vec::new();
-    let mut doubled_cols = Vec::new();
+// The puzzle text only gives part 2 answers for expansion factors of 10 and 100
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+",
+        part1: Some("374"),
+        part2: None,
+    },
+];
+
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Cosmic Expansion";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Image;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let map = Grid::parse(input, ".#")?;
+
+        let galaxies = map.find_all(|&c| c == GALAXY).collect();
+        let doubled_rows = (0..map.height()).filter(|&row| map.row(row).iter().all(|&c| c == SPACE)).collect();
+        let doubled_cols = (0..map.width()).filter(|&col| map.column(col).all(|&c| c == SPACE)).collect();
+
+        debug!(?doubled_rows, ?doubled_cols);
+
+        Ok(Image { galaxies, doubled_rows, doubled_cols })
+    }
 
-    for (row_num, row) in map.iter().enumerate() {
-        for (col_num, &ch) in row.iter().enumerate() {
-            if ch == GALAXY {
-                galaxies.push((row_num, col_num));
+    fn part1(image: &Self::Input) -> PartResult {
+        let Image { galaxies, doubled_rows, doubled_cols } = image;
+        let mut total_distance = 0;
+
+        for (row, col) in galaxies {
+            for (other_row, other_col) in galaxies {
+                if other_row > row || (other_row == row && other_col > col) {
+                    let mut distance = row.abs_diff(*other_row) + col.abs_diff(*other_col);
+                    distance += doubled_rows.iter().filter(|dr| row.min(other_row) < *dr && *dr < row.max(other_row)).count();
+                    distance += doubled_cols.iter().filter(|dc| col.min(other_col) < *dc && *dc < col.max(other_col)).count();
+                    total_distance += distance;
+                }
             }
         }
 
-        if row.iter().all(|&c| c == SPACE) {
-            doubled_rows.push(row_num);
-        }
-    }
-
-    for col_num in 0..map[0].len() {
-        if (0..map.len()).all(|row_num| map[row_num][col_num] == SPACE) {
-            doubled_cols.push(col_num);
-        }
+        info!(day=11, part=1, answer=total_distance);
+        Ok(Some(total_distance.into()))
     }
 
-    debug!(?doubled_rows, ?doubled_cols);
-
-    let mut total_distance = 0;
-
-    for (row, col) in &galaxies {
-        for (other_row, other_col) in &galaxies {
-            if other_row > row || (other_row == row && other_col > col) {
-                let mut distance = row.abs_diff(*other_row) + col.abs_diff(*other_col);
-                distance += doubled_rows.iter().filter(|dr| row.min(other_row) < *dr && *dr < row.max(other_row)).count();
-                distance += doubled_cols.iter().filter(|dc| col.min(other_col) < *dc && *dc < col.max(other_col)).count();
-                total_distance += distance;
+    fn part2(image: &Self::Input) -> PartResult {
+        let Image { galaxies, doubled_rows, doubled_cols } = image;
+        let mut total_distance = 0;
+
+        for (row, col) in galaxies {
+            for (other_row, other_col) in galaxies {
+                if other_row > row || (other_row == row && other_col > col) {
+                    let mut distance = row.abs_diff(*other_row) + col.abs_diff(*other_col);
+                    distance += doubled_rows.iter().filter(|dr| row.min(other_row) < *dr && *dr < row.max(other_row)).count() * (PART2_FACTOR - 1);
+                    distance += doubled_cols.iter().filter(|dc| col.min(other_col) < *dc && *dc < col.max(other_col)).count() * (PART2_FACTOR - 1);
+                    total_distance += distance;
+                }
             }
         }
-    }
-
-    info!(day=11, part=1, answer=total_distance);
-
-    total_distance = 0;
 
-    for (row, col) in &galaxies {
-        for (other_row, other_col) in &galaxies {
-            if other_row > row || (other_row == row && other_col > col) {
-                let mut distance = row.abs_diff(*other_row) + col.abs_diff(*other_col);
-                distance += doubled_rows.iter().filter(|dr| row.min(other_row) < *dr && *dr < row.max(other_row)).count() * (PART2_FACTOR - 1);
-                distance += doubled_cols.iter().filter(|dc| col.min(other_col) < *dc && *dc < col.max(other_col)).count() * (PART2_FACTOR - 1);
-                total_distance += distance;
-            }
-        }
+        info!(day=11, part=2, answer=total_distance);
+        Ok(Some(total_distance.into()))
     }
-
-    info!(day=11, part=2, answer=total_distance);
-
-    Ok(())
 }
@@ -1,16 +1,18 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use color_eyre::Report;
-use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, newline, one_of, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::{many1, separated_list1};
-use nom::sequence::{terminated, tuple};
-use tracing::{debug, info};
+use nom::character::complete::space1;
+use nom::combinator::map_res;
+use nom::multi::many1;
+use nom::sequence::tuple;
+use tracing::{debug, info, instrument};
+
+use crate::parse::{IResult, comma_separated, lines, one_of, parse_all, unsigned};
+use crate::render::{self, Canvas, Cell, Color, Style};
+use crate::solver::{Example, PartResult, Puzzle};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Spring {
+pub enum Spring {
     Ok,
     Damaged,
     Unknown
@@ -30,21 +32,18 @@ impl TryFrom<char> for Spring {
 }
 
 #[derive(Debug, Clone)]
-struct Row {
-    springs: Vec<Spring>,
-    groups: Vec<i32>
+pub struct Row {
+    pub springs: Vec<Spring>,
+    pub groups: Vec<i32>
 }
 
-fn parse_row(input: &str) -> IResult<&str, Row> {
+pub fn parse_row(input: &str) -> IResult<&str, Row> {
     tuple((
         many1(
             map_res(one_of(".#?"), Spring::try_from)
         ),
         space1,
-        separated_list1(
-            tag(","),
-            map_res(digit1, |n: &str| n.parse())
-        )
+        comma_separated(unsigned)
     ))(input).map(
         |(rest, (springs, _, groups))|
         (rest, Row { springs, groups })
@@ -76,25 +75,19 @@ fn parse_row(input: &str) -> IResult<&str, Row> {
 
 #[allow(dead_code)]
 fn show(springs: &[Spring], groups: &[i32], comment: &str) {
-    for spring in springs {
-        match spring {
-            Spring::Ok => print!("."),
-            Spring::Damaged => print!("#"),
-            Spring::Unknown => print!("?"),
-        }
-    }
-    if groups.is_empty() {
-        print!(" -");
-    } else {
-        print!(" {}", groups[0]);
-        for g in &groups[1..] {
-            print!(",{}", g);
-        }
-    }
+    let groups: Vec<String> = groups.iter().map(|g| g.to_string()).collect();
+    let mut title = if groups.is_empty() { "-".to_string() } else { groups.join(",") };
     if !comment.is_empty() {
-        print!("\t\t{}", comment);
+        title.push_str(&format!("\t\t{comment}"));
     }
-    println!();
+    render::show(&title, || {
+        let row = springs.iter().map(|spring| match spring {
+            Spring::Ok => Cell::plain('.'),
+            Spring::Damaged => Cell::new('#', Style::fg(Color::BrightYellow)),
+            Spring::Unknown => Cell::new('?', Style::fg(Color::BrightBlack)),
+        }).collect();
+        Canvas { rows: vec![row] }
+    });
 }
 
 impl Row {
@@ -128,12 +121,12 @@ impl Row {
     //     possibilities
     // }
 
-    fn count_possibilities(&self) -> i64 {
+    pub fn count_possibilities(&self) -> i64 {
         let cache = vec![vec![-1; self.groups.len() + 1]; self.springs.len() + 1];
         count_matches(&self.springs, &self.groups, None, Rc::new(RefCell::new(cache)))
     }
 
-    fn unfold(&self) -> Row {
+    pub fn unfold(&self) -> Row {
         let mut springs = Vec::new();
         springs.extend_from_slice(&self.springs);
         for _ in 0..4 {
@@ -146,7 +139,8 @@ impl Row {
     }
 }
 
-fn count_matches(springs: &[Spring], groups: &[i32], match_first_as: Option<Spring>, cache: Rc<RefCell<Vec<Vec<i64>>>>) -> i64 {
+#[instrument(level = "debug", skip_all)]
+pub fn count_matches(springs: &[Spring], groups: &[i32], match_first_as: Option<Spring>, cache: Rc<RefCell<Vec<Vec<i64>>>>) -> i64 {
     // show(springs, groups, &format!("{:?}", match_first_as));
     if springs.is_empty() {
         return if groups.is_empty() { 1 } else { 0 }
@@ -194,35 +188,58 @@ fn count_matches(springs: &[Spring], groups: &[i32], match_first_as: Option<Spri
     count
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-    let (_, rows) = all_consuming(
-        many1(
-            terminated(
-                parse_row,
-                newline
-            )
-        )
-    )(&input).unwrap();
-
-    debug!(?rows);
-
-    let mut total_possibilities = 0;
-    for row in &rows {
-        total_possibilities += row.count_possibilities();
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+",
+        part1: Some("21"),
+        part2: Some("525152"),
+    },
+];
+
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    const DAY: u32 = 12;
+    const TITLE: &'static str = "Hot Springs";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<Row>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let rows = parse_all(input, lines(parse_row))?;
+
+        debug!(?rows);
+        Ok(rows)
     }
 
-    info!(day=12, part=1, answer=total_possibilities);
-
-    let part2_rows: Vec<_> = rows.iter().map(|r| r.unfold()).collect();
-    let mut part2_possibilities = 0;
+    fn part1(rows: &Self::Input) -> PartResult {
+        let mut total_possibilities = 0;
+        for row in rows {
+            total_possibilities += row.count_possibilities();
+        }
 
-    for row in &part2_rows {
-        // show(&row.springs, &row.groups, "");
-        let possibilities = row.count_possibilities();
-        debug!(possibilities);
-        part2_possibilities += possibilities;
+        info!(day=12, part=1, answer=total_possibilities);
+        Ok(Some(total_possibilities.into()))
     }
-    info!(day=12, part=2, answer=part2_possibilities);
 
-    Ok(())
+    fn part2(rows: &Self::Input) -> PartResult {
+        let part2_rows: Vec<_> = rows.iter().map(|r| r.unfold()).collect();
+        let mut part2_possibilities = 0;
+
+        for row in &part2_rows {
+            // show(&row.springs, &row.groups, "");
+            let possibilities = row.count_possibilities();
+            debug!(possibilities);
+            part2_possibilities += possibilities;
+        }
+        info!(day=12, part=2, answer=part2_possibilities);
+        Ok(Some(part2_possibilities.into()))
+    }
 }
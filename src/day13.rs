@@ -2,7 +2,10 @@ use std::ops::BitXor;
 use color_eyre::Report;
 use tracing::{debug, info};
 
-fn row_to_u64(row: &[u8]) -> u64 {
+use crate::grid::Grid;
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub fn row_to_u64(row: &[u8]) -> u64 {
     let mut value = 0;
     for &c in row {
         value <<= 1;
@@ -13,17 +16,7 @@ fn row_to_u64(row: &[u8]) -> u64 {
     value
 }
 
-fn rotate_grid(rows: &[Vec<u8>]) -> Vec<Vec<u8>> {
-    let mut out = vec![Vec::new(); rows[0].len()];
-    for row in rows {
-        for (idx, col) in row.iter().enumerate() {
-            out[idx].push(*col);
-        }
-    }
-    out
-}
-
-fn find_reflections(grid: &[u64]) -> Vec<usize> {
+pub fn find_reflections(grid: &[u64]) -> Vec<usize> {
     let mut reflections = Vec::new();
     for i in 1..grid.len() {
         let mut left = i - 1;
@@ -41,7 +34,7 @@ fn find_reflections(grid: &[u64]) -> Vec<usize> {
     reflections
 }
 
-fn find_reflections_pt2(grid: &[u64]) -> Vec<usize> {
+pub fn find_reflections_pt2(grid: &[u64]) -> Vec<usize> {
     let mut reflections = Vec::new();
     for i in 1..grid.len() {
         let mut left = i - 1;
@@ -60,33 +53,75 @@ fn find_reflections_pt2(grid: &[u64]) -> Vec<usize> {
     reflections
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-    let mut grids: Vec<Vec<Vec<u8>>> = Vec::new();
-    for grid_str in input.split("\n\n") {
-        grids.push(grid_str.lines().map(|line| line.as_bytes().to_vec()).collect());
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+",
+        part1: Some("405"),
+        part2: Some("400"),
+    },
+];
+
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Point of Incidence";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<Grid<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        Ok(Grid::parse_many(input, ".#")?)
     }
 
-    let mut part1_total = 0;
-    let mut part2_total = 0;
-
-    for grid in grids {
-        let grid_u64: Vec<u64> = grid.iter().map(|row| row_to_u64(row)).collect();
-        let rotated = rotate_grid(&grid);
-        let rotated_u64: Vec<u64> = rotated.iter().map(|row| row_to_u64(row)).collect();
-        let reflections = find_reflections(&grid_u64);
-        let rotated_reflections = find_reflections(&rotated_u64);
-        debug!(?reflections, ?rotated_reflections);
-        part1_total += rotated_reflections.iter().sum::<usize>();
-        part1_total += reflections.iter().sum::<usize>() * 100;
-
-        let part2_reflections = find_reflections_pt2(&grid_u64);
-        let part2_rotated_reflections = find_reflections_pt2(&rotated_u64);
-        part2_total += part2_rotated_reflections.iter().sum::<usize>();
-        part2_total += part2_reflections.iter().sum::<usize>() * 100;
+    fn part1(grids: &Self::Input) -> PartResult {
+        let mut part1_total = 0;
+
+        for grid in grids {
+            let grid_u64: Vec<u64> = grid.rows().map(row_to_u64).collect();
+            let rotated = grid.transpose();
+            let rotated_u64: Vec<u64> = rotated.rows().map(row_to_u64).collect();
+            let reflections = find_reflections(&grid_u64);
+            let rotated_reflections = find_reflections(&rotated_u64);
+            debug!(?reflections, ?rotated_reflections);
+            part1_total += rotated_reflections.iter().sum::<usize>();
+            part1_total += reflections.iter().sum::<usize>() * 100;
+        }
+
+        info!(day=13, part=1, answer=part1_total);
+        Ok(Some(part1_total.into()))
     }
 
-    info!(day=13, part=1, answer=part1_total);
-    info!(day=13, part=2, answer=part2_total);
+    fn part2(grids: &Self::Input) -> PartResult {
+        let mut part2_total = 0;
+
+        for grid in grids {
+            let grid_u64: Vec<u64> = grid.rows().map(row_to_u64).collect();
+            let rotated = grid.transpose();
+            let rotated_u64: Vec<u64> = rotated.rows().map(row_to_u64).collect();
+            let part2_reflections = find_reflections_pt2(&grid_u64);
+            let part2_rotated_reflections = find_reflections_pt2(&rotated_u64);
+            part2_total += part2_rotated_reflections.iter().sum::<usize>();
+            part2_total += part2_reflections.iter().sum::<usize>() * 100;
+        }
 
-    Ok(())
+        info!(day=13, part=2, answer=part2_total);
+        Ok(Some(part2_total.into()))
+    }
 }
@@ -1,17 +1,21 @@
-use std::collections::HashMap;
-
 use color_eyre::Report;
-use tracing::{debug, info};
+use tracing::{debug, info, instrument};
+
+use crate::cycle::find_hashed;
+use crate::grid::Grid;
+use crate::render::{self, Canvas, Color, Style};
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub const ROUND_ROCK: u8 = b'O';
+pub const SQUARE_ROCK: u8 = b'#';
+pub const GROUND: u8 = b'.';
 
-const ROUND_ROCK: u8 = b'O';
-#[allow(dead_code)]
-const SQUARE_ROCK: u8 = b'#';
-const GROUND: u8 = b'.';
+pub const SPIN_CYCLES: usize = 1000000000;
 
-fn part1_load(grid: &[Vec<u8>]) -> i64 {
+pub fn part1_load(grid: &Grid<u8>) -> i64 {
     let mut load = 0;
-    for (idx, row) in grid.iter().enumerate() {
-        let rock_value = grid.len() - idx;
+    for (idx, row) in grid.rows().enumerate() {
+        let rock_value = grid.height() - idx;
         load += rock_value * row.iter().filter(|c| **c == ROUND_ROCK).count()
     }
     load as i64
@@ -20,114 +24,139 @@ fn part1_load(grid: &[Vec<u8>]) -> i64 {
 // I was going to do a rotate then roll, but I figured I'd wait until p2 to see whether the
 // roll operation had to be optimized.  Now it doesn't seem worth changing.
 
-fn roll_north(grid: &mut Vec<Vec<u8>>) {
-    for col_num in 0..grid[0].len() {
-        for mut row_num in 0..grid.len() {
-            if grid[row_num][col_num] == ROUND_ROCK {
-                while row_num > 0 && grid[row_num - 1][col_num] == GROUND {
+pub fn roll_north(grid: &mut Grid<u8>) {
+    for col_num in 0..grid.width() {
+        for mut row_num in 0..grid.height() {
+            if grid[(row_num, col_num)] == ROUND_ROCK {
+                while row_num > 0 && grid[(row_num - 1, col_num)] == GROUND {
                     row_num -= 1;
                     debug!(row=row_num, col=col_num, "up");
-                    grid[row_num][col_num] = ROUND_ROCK;
-                    grid[row_num + 1][col_num] = GROUND;
+                    grid[(row_num, col_num)] = ROUND_ROCK;
+                    grid[(row_num + 1, col_num)] = GROUND;
                 }
             }
         }
     }
 }
 
-fn roll_south(grid: &mut Vec<Vec<u8>>) {
-    for col_num in 0..grid[0].len() {
-        for mut row_num in (0..grid.len()).rev() {
-            if grid[row_num][col_num] == ROUND_ROCK {
-                while row_num < grid.len() - 1 && grid[row_num + 1][col_num] == GROUND {
+pub fn roll_south(grid: &mut Grid<u8>) {
+    for col_num in 0..grid.width() {
+        for mut row_num in (0..grid.height()).rev() {
+            if grid[(row_num, col_num)] == ROUND_ROCK {
+                while row_num < grid.height() - 1 && grid[(row_num + 1, col_num)] == GROUND {
                     row_num += 1;
                     debug!(row=row_num, col=col_num, "down");
-                    grid[row_num][col_num] = ROUND_ROCK;
-                    grid[row_num - 1][col_num] = GROUND;
+                    grid[(row_num, col_num)] = ROUND_ROCK;
+                    grid[(row_num - 1, col_num)] = GROUND;
                 }
             }
         }
     }
 }
 
-fn roll_west(grid: &mut Vec<Vec<u8>>) {
-    for row_num in 0..grid.len() {
-        for mut col_num in 0..grid[0].len() {
-            if grid[row_num][col_num] == ROUND_ROCK {
-                while col_num > 0 && grid[row_num][col_num - 1] == GROUND {
+pub fn roll_west(grid: &mut Grid<u8>) {
+    for row_num in 0..grid.height() {
+        for mut col_num in 0..grid.width() {
+            if grid[(row_num, col_num)] == ROUND_ROCK {
+                while col_num > 0 && grid[(row_num, col_num - 1)] == GROUND {
                     col_num -= 1;
                     debug!(row=row_num, col=col_num, "left");
-                    grid[row_num][col_num] = ROUND_ROCK;
-                    grid[row_num][col_num + 1] = GROUND;
+                    grid[(row_num, col_num)] = ROUND_ROCK;
+                    grid[(row_num, col_num + 1)] = GROUND;
                 }
             }
         }
     }
 }
 
-fn roll_east(grid: &mut Vec<Vec<u8>>) {
-    for row_num in 0..grid.len() {
-        for mut col_num in (0..grid[0].len()).rev() {
-            if grid[row_num][col_num] == ROUND_ROCK {
-                while col_num < grid[0].len() - 1 && grid[row_num][col_num + 1] == GROUND {
+pub fn roll_east(grid: &mut Grid<u8>) {
+    for row_num in 0..grid.height() {
+        for mut col_num in (0..grid.width()).rev() {
+            if grid[(row_num, col_num)] == ROUND_ROCK {
+                while col_num < grid.width() - 1 && grid[(row_num, col_num + 1)] == GROUND {
                     col_num += 1;
                     debug!(row=row_num, col=col_num, "right");
-                    grid[row_num][col_num] = ROUND_ROCK;
-                    grid[row_num][col_num - 1] = GROUND;
+                    grid[(row_num, col_num)] = ROUND_ROCK;
+                    grid[(row_num, col_num - 1)] = GROUND;
                 }
             }
         }
     }
 }
 
-fn cycle(grid: &mut Vec<Vec<u8>>) {
+#[instrument(level = "debug", skip_all)]
+pub fn cycle(grid: &mut Grid<u8>) {
     roll_north(grid);
     roll_west(grid);
     roll_south(grid);
     roll_east(grid);
 }
 
-fn show(grid: &[Vec<u8>]) {
-    for row in grid {
-        for col in row {
-            print!("{}", *col as char);
-        }
-        println!();
-    }
+fn show(title: &str, grid: &Grid<u8>) {
+    render::show(title, || Canvas::from_bytes(grid, |b| match b {
+        ROUND_ROCK => Style::fg(Color::BrightYellow),
+        SQUARE_ROCK => Style::fg(Color::BrightBlack),
+        _ => Style::default(),
+    }));
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-
-    let mut grid: Vec<Vec<u8>> = input.lines().map(|line| line.as_bytes().to_vec()).collect();
-    show(&grid);
-    println!();
-    roll_north(&mut grid);
-    show(&grid);
-
-    let load = part1_load(&grid);
-    info!(day=14, part=1, answer=load);
-
-    let mut seen_grids = HashMap::new();
-
-    let target = 1000000000;
-
-    for iter in 1..100000 {
-        cycle(&mut grid);
-        let mut state: Vec<u8> = Vec::with_capacity(grid.len() * grid[0].len());
-        for row in grid.iter() { state.extend_from_slice(row) };
-        let seen = seen_grids.get(&state);
-        if let Some(s) = seen {
-            let cycle = iter - s;
-            let load = part1_load(&grid);
-            info!("duplicate: {} = {} (cycle {}) load={}", iter, s, cycle, load);
-            if (iter - s) % cycle == (target - s) % cycle {
-                info!(day=14, part=2, answer=load);
-                break;
-            }
-        } else {
-            seen_grids.insert(state, iter);
-        }
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+",
+        part1: Some("136"),
+        part2: Some("64"),
+    },
+];
+
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Parabolic Reflector Dish";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        Ok(Grid::parse(input, "O#.")?)
     }
 
-    Ok(())
+    fn part1(grid: &Self::Input) -> PartResult {
+        let mut grid = grid.clone();
+        show("day 14 before tilting north", &grid);
+        roll_north(&mut grid);
+        show("day 14 after tilting north", &grid);
+
+        let load = part1_load(&grid);
+        info!(day=14, part=1, answer=load);
+        Ok(Some(load.into()))
+    }
+
+    fn part2(grid: &Self::Input) -> PartResult {
+        let spin = |grid: &Grid<u8>| {
+            let mut grid = grid.clone();
+            cycle(&mut grid);
+            grid
+        };
+        let found = find_hashed(grid, spin);
+        info!(mu=found.mu, lambda=found.lambda, "spin cycles repeat");
+
+        let grid = found.state_after(grid, SPIN_CYCLES, spin);
+        show("day 14 after all the spin cycles", &grid);
+
+        let load = part1_load(&grid);
+        info!(day=14, part=2, answer=load);
+        Ok(Some(load.into()))
+    }
 }
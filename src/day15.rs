@@ -1,11 +1,15 @@
 use color_eyre::Report;
-use nom::character::complete::{alpha1, digit1, one_of};
-use nom::combinator::{all_consuming, map_res, opt};
-use nom::IResult;
-use nom::sequence::tuple;
+use nom::branch::alt;
+use nom::character::complete::alpha1;
+use nom::combinator::{consumed, cut, map, value};
+use nom::error::context;
+use nom::sequence::{preceded, tuple};
 use tracing::{debug, info};
 
-fn hash(s: &str) -> u8 {
+use crate::parse::{IResult, comma_separated, parse_all, tag, unsigned};
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub fn hash(s: &str) -> u8 {
     let mut value = 0;
     for ch in s.chars() {
         value += (ch as u8) as i32;
@@ -15,72 +19,110 @@ fn hash(s: &str) -> u8 {
     value as u8
 }
 
-enum Operation<'a> {
-    Insert(&'a str, i32),
-    Remove(&'a str)
+pub enum Operation {
+    Insert(String, i32),
+    Remove(String)
+}
+
+/// One step of the initialization sequence: its text, which is what gets hashed, and what it does
+pub struct Step {
+    pub text: String,
+    pub operation: Operation,
 }
 
-fn parse_operation(input: &str) -> IResult<&str, Operation> {
+pub fn parse_operation(input: &str) -> IResult<&str, Operation> {
     tuple((
         alpha1,
-        one_of("-="),
-        opt(
-            map_res(digit1, |s: &str| s.parse())
-        )
-    ))(input).map(|(rest, (tag, op, num))|
-        (rest, match op {
-                '=' => Operation::Insert(tag, num.unwrap()),
-                '-' => Operation::Remove(tag),
-                _ => unreachable!()
+        context("'=' and a focal length, or '-'", alt((
+            map(preceded(tag("="), unsigned), Some),
+            value(None, tag("-")),
+        )))
+    ))(input).map(|(rest, (label, num))|
+        (rest, match num {
+                Some(num) => Operation::Insert(label.to_string(), num),
+                None => Operation::Remove(label.to_string()),
             }
         )
     )
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+pub fn parse_step(input: &str) -> IResult<&str, Step> {
+    consumed(parse_operation)(input).map(|(rest, (text, operation))|
+        (rest, Step { text: text.to_string(), operation })
+    )
+}
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        // The puzzle text also hashes "HASH" to 52, but that isn't a step, so it can't be an input
+        input: "rn=1\n",
+        part1: Some("30"),
+        part2: Some("1"),
+    },
+    Example {
+        input: "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n",
+        part1: Some("1320"),
+        part2: Some("145"),
+    },
+];
+
+pub struct Day15;
 
-    let strings: Vec<&str> = input.trim().split(',').collect();
+impl Puzzle for Day15 {
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Lens Library";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
 
-    debug!(hash_hash=hash("HASH"));
+    type Input = Vec<Step>;
 
-    let mut hash_total: i32 = 0;
-    for s in &strings {
-        hash_total += hash(s) as i32;
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        // Trimming only the end keeps error positions lined up with the input
+        Ok(parse_all(input.trim_end(), comma_separated(cut(parse_step)))?)
     }
 
-    info!(day=1, part=1, answer=hash_total);
+    fn part1(steps: &Self::Input) -> PartResult {
+        debug!(hash_hash=hash("HASH"));
 
-    let mut table: Vec<Vec<(String, i32)>> = vec![vec![]; 256];
+        let mut hash_total: i32 = 0;
+        for step in steps {
+            hash_total += hash(&step.text) as i32;
+        }
 
-    'op: for s in &strings {
-        let (_, op) = all_consuming(parse_operation)(s).unwrap();
-        match op {
-            Operation::Insert(tag, value) => {
-                let bucket = hash(tag) as usize;
-                for (entry_tag, entry_value) in &mut table[bucket] {
-                    if entry_tag == tag {
-                        *entry_value = value;
-                        continue 'op;
+        info!(day=15, part=1, answer=hash_total);
+        Ok(Some(hash_total.into()))
+    }
+
+    fn part2(steps: &Self::Input) -> PartResult {
+        let mut table: Vec<Vec<(String, i32)>> = vec![vec![]; 256];
+
+        'op: for step in steps {
+            match &step.operation {
+                Operation::Insert(tag, value) => {
+                    let bucket = hash(tag) as usize;
+                    for (entry_tag, entry_value) in &mut table[bucket] {
+                        if entry_tag == tag {
+                            *entry_value = *value;
+                            continue 'op;
+                        }
                     }
+                    table[bucket].push((tag.to_string(), *value))
+                }
+                Operation::Remove(tag) => {
+                    let bucket = hash(tag) as usize;
+                    table[bucket].retain(|(entry_tag, _)| entry_tag != tag);
                 }
-                table[bucket].push((tag.to_string(), value))
-            }
-            Operation::Remove(tag) => {
-                let bucket = hash(tag) as usize;
-                table[bucket].retain(|(entry_tag, _)| entry_tag != tag);
             }
         }
-    }
 
-    let mut total = 0;
-    for (idx, bucket) in table.iter().enumerate() {
-        for (entry_idx, (tag, value)) in bucket.iter().enumerate() {
-            debug!(tag=tag, bx=(idx + 1), slot=(entry_idx + 1), value=value);
-            total += (idx + 1) * (entry_idx + 1) * (*value as usize);
+        let mut total = 0;
+        for (idx, bucket) in table.iter().enumerate() {
+            for (entry_idx, (tag, value)) in bucket.iter().enumerate() {
+                debug!(tag=tag, bx=(idx + 1), slot=(entry_idx + 1), value=value);
+                total += (idx + 1) * (entry_idx + 1) * (*value as usize);
+            }
         }
-    }
 
-    info!(day=1, part=2, answer=total);
-
-    Ok(())
+        info!(day=15, part=2, answer=total);
+        Ok(Some(total.into()))
+    }
 }
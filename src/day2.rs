@@ -2,26 +2,28 @@ use std::ops::Add;
 
 use color_eyre::Report;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, space0, space1};
-use nom::combinator::{map_res, value};
-use nom::IResult;
+use nom::character::complete::{space0, space1};
+use nom::combinator::{all_consuming, value};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
+use nom::Finish;
 use tracing::{debug, info};
 
+use crate::parse::{IResult, ParseError, comma_separated, tag, unsigned};
+use crate::solver::{Example, PartResult, Puzzle};
+
 #[derive(Debug, Copy, Clone)]
-enum Color {
+pub enum Color {
     Red,
     Green,
     Blue,
 }
 
 #[derive(Debug, Eq, PartialEq, Default)]
-struct CubeSet {
-    red: i32,
-    green: i32,
-    blue: i32,
+pub struct CubeSet {
+    pub red: i32,
+    pub green: i32,
+    pub blue: i32,
 }
 
 impl Add for CubeSet {
@@ -37,11 +39,11 @@ impl Add for CubeSet {
 }
 
 impl CubeSet {
-    fn contains(&self, other: &CubeSet) -> bool {
+    pub fn contains(&self, other: &CubeSet) -> bool {
         self.red >= other.red && self.green >= other.green && self.blue >= other.blue
     }
 
-    fn add_cubes(&mut self, count: i32, color: Color) {
+    pub fn add_cubes(&mut self, count: i32, color: Color) {
         match color {
             Color::Red => self.red += count,
             Color::Green => self.blue += count,
@@ -49,20 +51,20 @@ impl CubeSet {
         }
     }
 
-    fn ensure_contains(&mut self, other: &CubeSet) {
+    pub fn ensure_contains(&mut self, other: &CubeSet) {
         if self.red < other.red { self.red = other.red }
         if self.green < other.green { self.green = other.green }
         if self.blue < other.blue { self.blue = other.blue }
     }
 
-    fn power(&self) -> i32 {
+    pub fn power(&self) -> i32 {
         self.red * self.green * self.blue
     }
 }
 
-fn parse_color(input: &str) -> IResult<&str, (i32, Color)> {
+pub fn parse_color(input: &str) -> IResult<&str, (i32, Color)> {
     tuple((
-        map_res(digit1, |s: &str| s.parse()),
+        unsigned,
         space1,
         alt((
             value(Color::Red, tag("red")),
@@ -72,14 +74,8 @@ fn parse_color(input: &str) -> IResult<&str, (i32, Color)> {
     ))(input).map(|(rest, (count, _, color))| (rest, (count, color)))
 }
 
-fn parse_cubeset(input: &str) -> IResult<&str, CubeSet> {
-    separated_list1(
-        tuple((
-            tag(","),
-            space0
-        )),
-        parse_color
-    )(input).map(|(rest, colors)| {
+pub fn parse_cubeset(input: &str) -> IResult<&str, CubeSet> {
+    comma_separated(parse_color)(input).map(|(rest, colors)| {
         let mut cs = CubeSet::default();
         for (count, color) in colors {
             cs.add_cubes(count, color);
@@ -88,10 +84,10 @@ fn parse_cubeset(input: &str) -> IResult<&str, CubeSet> {
     })
 }
 
-fn parse_game(input: &str) -> IResult<&str, (i32, Vec<CubeSet>)> {
+pub fn parse_game(input: &str) -> IResult<&str, (i32, Vec<CubeSet>)> {
     tuple((
         tag("Game "),
-        map_res(digit1, |s: &str| s.parse()),
+        unsigned,
         tag(": "),
         separated_list1(
             tuple((tag(";"), space0)),
@@ -102,36 +98,69 @@ fn parse_game(input: &str) -> IResult<&str, (i32, Vec<CubeSet>)> {
     )
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-
-    let (_, bag) = parse_cubeset("12 red, 13 green, 14 blue").unwrap();
-    debug!(bag=?bag);
-
-    let mut part1 = 0;
-    'games: for line in input.lines() {
-        let (_, (game_id, cubesets)) = parse_game(line).expect("unparseable game");
-        for cubeset in cubesets {
-            if !bag.contains(&cubeset) {
-                debug!(bag=?bag, cubeset=?cubeset, "impossible");
-                continue 'games;
-            }
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+",
+        part1: Some("8"),
+        part2: Some("2286"),
+    },
+];
+
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<(i32, Vec<CubeSet>)>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let mut games = Vec::new();
+        for line in input.lines() {
+            let (_, game) = all_consuming(parse_game)(line).finish()
+                .map_err(|e| ParseError::from_nom(input, e))?;
+            games.push(game);
         }
-        part1 += game_id;
+        Ok(games)
     }
 
-    info!(day=2, part=1, answer=part1);
-
-    let mut part2 = 0;
-    for line in input.lines() {
-        let mut minimum_set = CubeSet::default();
-        let (_, (_, cubesets)) = parse_game(line).expect("unparseable game");
-        for cubeset in cubesets {
-            minimum_set.ensure_contains(&cubeset);
+    fn part1(games: &Self::Input) -> PartResult {
+        let (_, bag) = parse_cubeset("12 red, 13 green, 14 blue").unwrap();
+        debug!(bag=?bag);
+
+        let mut part1 = 0;
+        'games: for (game_id, cubesets) in games {
+            for cubeset in cubesets {
+                if !bag.contains(cubeset) {
+                    debug!(bag=?bag, cubeset=?cubeset, "impossible");
+                    continue 'games;
+                }
+            }
+            part1 += game_id;
         }
-        part2 += minimum_set.power();
+
+        info!(day=2, part=1, answer=part1);
+        Ok(Some(part1.into()))
     }
 
-    info!(day=2, part=2, answer=part2);
+    fn part2(games: &Self::Input) -> PartResult {
+        let mut part2 = 0;
+        for (_, cubesets) in games {
+            let mut minimum_set = CubeSet::default();
+            for cubeset in cubesets {
+                minimum_set.ensure_contains(cubeset);
+            }
+            part2 += minimum_set.power();
+        }
 
-    Ok(())
+        info!(day=2, part=2, answer=part2);
+        Ok(Some(part2.into()))
+    }
 }
@@ -1,117 +1,133 @@
 use color_eyre::Report;
 use fnv::FnvHashSet;
 use regex::Regex;
-use tracing::info;
+use tracing::{info, trace};
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
-struct Cell {
-    row: i32,
-    col: i32,
-}
+use crate::grid::{Grid, Pos};
+use crate::parse::{Expected, ParseError};
+use crate::solver::{Example, PartResult, Puzzle};
 
 #[derive(Debug, Eq, PartialEq)]
-struct Number {
-    cell: Cell,
-    value: i32,
+pub struct Number {
+    pub pos: Pos,
+    pub len: usize,
+    pub value: i32,
 }
 
 #[derive(Debug)]
-struct Symbol {
-    cell: Cell,
-    symbol: char,
+pub struct Symbol {
+    pub pos: Pos,
+    pub symbol: char,
 }
 
 impl Number {
-    fn occupied_cells(&self) -> Vec<Cell> {
-        let mut n = self.value;
-        let mut col = self.cell.col;
-        let mut cells = Vec::new();
-        while n > 0 {
-            cells.push(Cell { row: self.cell.row, col });
-            n /= 10;
-            col += 1;
-        }
-        cells
+    pub fn occupied_cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.len).map(|i| (self.pos.0, self.pos.1 + i))
     }
 }
 
-impl Cell {
-    fn adjacent_cells(&self) -> FnvHashSet<Cell> {
-        let mut set = FnvHashSet::default();
-        for &drow in &[-1, 0, 1] {
-            for &dcol in &[-1, 0, 1] {
-                if drow == 0 && dcol == 0 { continue }
-                let arow = self.row + drow;
-                let acol = self.col + dcol;
-                if arow >= 0 && acol >= 0 {
-                    set.insert(Cell { row: arow, col: acol });
-                }
-            }
-        }
-        set
+#[derive(Debug)]
+pub struct Schematic {
+    pub grid: Grid<u8>,
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    /// The cells around `pos`, diagonals included
+    pub fn adjacent_cells(&self, pos: Pos) -> FnvHashSet<Pos> {
+        self.grid.neighbours8(pos).collect()
     }
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-    let mut numbers = Vec::new();
-    let mut symbols = Vec::new();
-    let re = Regex::new(r"(\d+)|([^.])").unwrap();
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+",
+        part1: Some("4361"),
+        part2: Some("467835"),
+    },
+];
+
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
 
-    for (row, line) in input.lines().enumerate() {
-        println!("{}", line);
-        for caps in re.captures_iter(line) {
-            if let Some(m) = caps.get(1) {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let grid = Grid::parse_any(input)?;
+        let mut numbers = Vec::new();
+        let re = Regex::new(r"\d+").unwrap();
+
+        for (row, line) in input.lines().enumerate() {
+            trace!(line);
+            for m in re.find_iter(line) {
                 numbers.push(Number {
-                    cell: Cell { row: row as i32, col: m.start() as i32 },
-                    value: m.as_str().parse().unwrap()
-                })
-            } else if let Some(m) = caps.get(2) {
-                symbols.push(Symbol {
-                    cell: Cell { row: row as i32, col: m.start() as i32 },
-                    symbol: m.as_str().chars().next().unwrap()
+                    pos: (row, m.start()),
+                    len: m.len(),
+                    value: m.as_str().parse().map_err(|_| ParseError::new(input, &line[m.start()..], Expected::Fits("i32")))?
                 })
-            } else {
-                panic!("no match for group 1 or 2");
             }
         }
-    }
 
-    let mut part1 = 0;
-    let mut adjacent_to_symbols = FnvHashSet::default();
-    for symbol in &symbols {
-        for cell in symbol.cell.adjacent_cells() {
-            adjacent_to_symbols.insert(cell);
-        }
+        let symbols = grid.find_all(|&c| c != b'.' && !c.is_ascii_digit())
+            .map(|pos| Symbol { pos, symbol: grid[pos] as char })
+            .collect();
+
+        Ok(Schematic { grid, numbers, symbols })
     }
 
-    for n in &numbers {
-        if n.occupied_cells().iter().any(|c| adjacent_to_symbols.contains(c)) {
-            part1 += n.value;
+    fn part1(schematic: &Self::Input) -> PartResult {
+        let mut part1 = 0;
+        let mut adjacent_to_symbols = FnvHashSet::default();
+        for symbol in &schematic.symbols {
+            adjacent_to_symbols.extend(schematic.adjacent_cells(symbol.pos));
         }
-    }
 
+        for n in &schematic.numbers {
+            if n.occupied_cells().any(|c| adjacent_to_symbols.contains(&c)) {
+                part1 += n.value;
+            }
+        }
 
-    info!(day=3, part=1, answer=part1);
+        info!(day=3, part=1, answer=part1);
+        Ok(Some(part1.into()))
+    }
 
-    let mut part2 = 0;
-    for symbol in symbols {
-        if symbol.symbol == '*' {
-            let adjacent_cells = symbol.cell.adjacent_cells();
-            let mut number_count = 0;
-            let mut number_product = 1;
-            for n in &numbers {
-                if n.occupied_cells().iter().any(|oc| adjacent_cells.contains(oc)) {
-                    number_count += 1;
-                    number_product *= n.value;
+    fn part2(schematic: &Self::Input) -> PartResult {
+        let mut part2 = 0;
+        for symbol in &schematic.symbols {
+            if symbol.symbol == '*' {
+                let adjacent_cells = schematic.adjacent_cells(symbol.pos);
+                let mut number_count = 0;
+                let mut number_product = 1;
+                for n in &schematic.numbers {
+                    if n.occupied_cells().any(|oc| adjacent_cells.contains(&oc)) {
+                        number_count += 1;
+                        number_product *= n.value;
+                    }
+                }
+                if number_count == 2 {
+                    part2 += number_product;
                 }
-            }
-            if number_count == 2 {
-                part2 += number_product;
             }
         }
-    }
-
-    info!(day=3, part=2, answer=part2);
 
-    Ok(())
+        info!(day=3, part=2, answer=part2);
+        Ok(Some(part2.into()))
+    }
 }
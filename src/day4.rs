@@ -1,23 +1,22 @@
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
-use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, newline, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::{many1, separated_list1};
-use nom::sequence::{terminated, tuple};
+use nom::character::complete::space1;
+use nom::sequence::tuple;
 use tracing::{debug, info};
 
-#[derive(Debug)]
-struct Card {
-    #[allow(dead_code)]
-    id: i32,
-    winning_numbers: Vec<i32>,
-    have_numbers: Vec<i32>,
-    copies: i32,
+use crate::parse::{IResult, lines, parse_all, tag, unsigned, unsigned_numbers};
+use crate::solver::{Example, PartResult, Puzzle};
+
+#[derive(Debug, Clone)]
+pub struct Card {
+    pub id: i32,
+    pub winning_numbers: Vec<i32>,
+    pub have_numbers: Vec<i32>,
+    pub copies: i32,
 }
 
 impl Card {
-    fn winning_number_count(&self) -> i32 {
+    pub fn winning_number_count(&self) -> i32 {
         let mut matches = 0;
         for have_number in &self.have_numbers {
             for winning_number in &self.winning_numbers {
@@ -29,7 +28,7 @@ impl Card {
         matches
     }
 
-    fn part1_value(&self) -> i32 {
+    pub fn part1_value(&self) -> i32 {
         let matches = self.winning_number_count();
         if matches > 0 {
             1 << (matches - 1)
@@ -39,51 +38,75 @@ impl Card {
     }
 }
 
-fn parse_card(input: &str) -> IResult<&str, Card> {
+pub fn parse_card(input: &str) -> IResult<&str, Card> {
     tuple((
         tag("Card"),
         space1,
-        map_res(digit1, |s: &str| s.parse()),
+        unsigned,
         tag(":"),
         space1,
-        separated_list1(
-            space1,
-            map_res(digit1, |s: &str| s.parse()),
-        ),
+        unsigned_numbers,
         space1,
         tag("|"),
         space1,
-        separated_list1(
-            space1,
-            map_res(digit1, |s: &str| s.parse()),
-        ),
+        unsigned_numbers,
     ))(input).map(|(rest, (_, _, id, _, _, winning_numbers, _, _, _, have_numbers))|
         (rest, (Card { id, winning_numbers, have_numbers, copies: 1 }))
     )
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+",
+        part1: Some("13"),
+        part2: Some("30"),
+    },
+];
 
-    let (_, mut cards) = all_consuming(
-        many1(
-            terminated(parse_card, newline)
-        )
-    )(&input).unwrap();
+pub struct Day4;
 
-    debug!(?cards);
+impl Puzzle for Day4 {
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Scratchcards";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
 
-    let total: i32 = cards.iter().map(|c| c.part1_value()).sum();
+    type Input = Vec<Card>;
 
-    info!(day=4, part=1, answer=total);
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let cards = parse_all(input, lines(parse_card))?;
 
-    for i in 0..cards.len() {
-        for n in 0..cards[i].winning_number_count() as usize {
-            cards[i + n + 1].copies += cards[i].copies;
-        }
+        debug!(?cards);
+        Ok(cards)
     }
 
-    let total_cards: i32 = cards.iter().map(|c| c.copies).sum();
-    info!(day=4, part=2, answer=total_cards);
+    fn part1(cards: &Self::Input) -> PartResult {
+        let total: i32 = cards.iter().map(|c| c.part1_value()).sum();
 
-    Ok(())
+        info!(day=4, part=1, answer=total);
+        Ok(Some(total.into()))
+    }
+
+    fn part2(cards: &Self::Input) -> PartResult {
+        let mut cards = cards.clone();
+        for i in 0..cards.len() {
+            let wins = cards[i].winning_number_count() as usize;
+            if i + wins >= cards.len() {
+                return Err(eyre!("card {} wins copies of {wins} cards after it, but there are only {}", cards[i].id, cards.len() - i - 1))
+            }
+            for n in 0..wins {
+                cards[i + n + 1].copies += cards[i].copies;
+            }
+        }
+
+        let total_cards: i32 = cards.iter().map(|c| c.copies).sum();
+        info!(day=4, part=2, answer=total_cards);
+        Ok(Some(total_cards.into()))
+    }
 }
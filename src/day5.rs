@@ -1,230 +1,201 @@
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
-use nom::bytes::complete::{tag, take_while};
-use nom::character::complete::{digit1, newline, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::{many1, separated_list1};
+use nom::bytes::complete::take_while;
+use nom::character::complete::{newline, space1};
+use nom::combinator::opt;
 use nom::sequence::{terminated, tuple};
-use tracing::{debug, info};
+use tracing::{debug, info, instrument};
 
-#[derive(Debug)]
-struct Range {
-    start: i64,
-    length: i64,
-    shift: i64,
-}
-
-impl Range {
-    fn empty() -> Range {
-        Range {
-            start: 0,
-            length: i64::MAX,
-            shift: 0
-        }
-    }
-
-    fn end(&self) -> i64 {
-        self.start + self.length
-    }
-
-    fn map_value(&self, value: i64) -> Option<i64> {
-        if value >= self.start && value < self.end() {
-            Some(value + self.shift)
-        } else {
-            None
-        }
-    }
-}
+use crate::parse::{Expected, IResult, NomError, blocks, field, lines, parse_all, tag, unsigned, unsigned_numbers};
+use crate::intervals::{Interval, IntervalSet, Shift, ShiftMap};
+use crate::solver::{Example, PartResult, Puzzle};
 
 #[derive(Debug)]
-struct Day5Map {
-    from: String,
-    to: String,
-    ranges: Vec<Range>
+pub struct Day5Map {
+    pub from: String,
+    pub to: String,
+    pub map: ShiftMap,
 }
 
 impl Day5Map {
-    fn new(from: String, to: String, mut ranges: Vec<Range>) -> Day5Map {
-        ranges.sort_by(|a, b| a.start.cmp(&b.start));
-        let mut new_ranges = Vec::new();
-        let mut last_end = 0;
-        for r in ranges {
-            let missing_length = r.start - last_end;
-            if missing_length > 1 {
-                new_ranges.push(Range { start: last_end, length: missing_length, shift: 0 })
-            }
-            last_end = r.end();
-            new_ranges.push(r);
-        }
-        if last_end != i64::MAX {
-            new_ranges.push(Range {
-                start: last_end,
-                length: i64::MAX - last_end,
-                shift: 0,
-            });
-        }
-        Day5Map { from, to, ranges: new_ranges }
-    }
-
-    fn range_for(&self, value: i64) -> &Range {
-        for r in &self.ranges {
-            if r.map_value(value).is_some() { return r }
-        }
-        debug!(?self.ranges);
-        panic!("missing range for {}", value);
-    }
-
-    fn flatten(&self, other: &Day5Map) -> Day5Map {
+    #[instrument(level = "debug", skip_all)]
+    pub fn flatten(&self, other: &Day5Map) -> Day5Map {
         if self.to != other.from {
             panic!("can't flatten map to {} with map from {}", self.to, other.from)
         }
 
-        let mut new_ranges = Vec::new();
-        for range in &self.ranges {
-            let mut pt = range.start;
-            while pt < range.end() {
-                let mapped = range.map_value(pt).unwrap();
-                let other_range = other.range_for(mapped);
-                debug!(?other_range);
-                let shift = range.shift + other_range.shift;
-                let length = (range.end() - pt).min(other_range.end() - mapped);
-                new_ranges.push(Range {
-                    start: pt,
-                    length,
-                    shift
-                });
-                debug!(pt, ?range, length);
-                debug!(?new_ranges);
-                pt += length;
-            }
-        }
-
-        Day5Map::new(self.from.to_string(), other.to.to_string(), new_ranges)
+        let map = self.map.compose(&other.map);
+        debug!(from=self.from, to=other.to, pieces=?map.pieces());
+        Day5Map { from: self.from.to_string(), to: other.to.to_string(), map }
     }
 }
 
-fn parse_seeds(input: &str) -> IResult<&str, Vec<i64>> {
-    terminated(
-        tuple((
-            tag("seeds:"),
-            space1,
-            separated_list1(
-                space1,
-                map_res(digit1, |s: &str| s.parse())
-            )
-        )),
-        newline
-    )(input).map(|(rest, (_, _, seeds))|
-        (rest, seeds)
-    )
+pub fn parse_seeds(input: &str) -> IResult<&str, Vec<i64>> {
+    terminated(field("seeds", unsigned_numbers), newline)(input)
 }
 
-fn parse_range(input: &str) -> IResult<&str, Range> {
+pub fn parse_range(input: &str) -> IResult<&str, Shift> {
     tuple((
-        map_res(digit1, |s: &str| s.parse::<i64>()),
+        unsigned::<i64>,
         space1,
-        map_res(digit1, |s: &str| s.parse::<i64>()),
+        unsigned::<i64>,
         space1,
-        map_res(digit1, |s: &str| s.parse::<i64>()),
+        unsigned::<i64>,
     ))(input).map(|(rest, (destination_start, _, source_start, _, length))|
-        (rest, Range { start: source_start, length, shift: destination_start - source_start })
+        (rest, Shift { interval: Interval::with_length(source_start, length), shift: destination_start - source_start })
     )
 }
 
-fn parse_map(input: &str) -> IResult<&str, Day5Map> {
-    terminated(
-        tuple((
-            take_while(|c: char| c.is_alphabetic()),
-            tag("-to-"),
-            take_while(|c: char| c.is_alphabetic()),
-            tag(" map:"),
-            newline,
-            many1(
-                terminated(
-                    parse_range,
-                    newline
-                )
-            )
-            )),
-        newline
-    )(input).map(|(rest, (from, _, to, _, _, ranges))|
-        (rest, Day5Map::new(from.to_string(), to.to_string(), ranges))
-    )
+pub fn parse_ranges(input: &str) -> IResult<&str, ShiftMap> {
+    let (rest, ranges) = lines(parse_range)(input)?;
+    match ShiftMap::new(ranges) {
+        Ok(map) => Ok((rest, map)),
+        Err(_) => Err(nom::Err::Failure(NomError { input, expected: Expected::Context("ranges that don't overlap") })),
+    }
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-    let (_, (seeds, maps)) = all_consuming(tuple((
-        parse_seeds,
+pub fn parse_map(input: &str) -> IResult<&str, Day5Map> {
+    tuple((
+        take_while(|c: char| c.is_alphabetic()),
+        tag("-to-"),
+        take_while(|c: char| c.is_alphabetic()),
+        tag(" map:"),
         newline,
-        many1(parse_map)
-    )))(&input).map(|(rest, (seeds, _, maps))|
-        (rest, (seeds, maps))
-    ).unwrap();
-
-    debug!(seeds=?seeds, maps=?maps);
-
-    let dest_map = "location";
-
-    // let mut lowest_location = None;
-    //
-    // for seed in &seeds {
-    //     let mut current_map = "seed";
-    //     let mut value = *seed;
-    //     while current_map != dest_map {
-    //         for map in &maps {
-    //             if map.from == current_map {
-    //                 let new_value = map.range_for(value).map_value(value).unwrap();
-    //                 debug!("mapping {} {} to {} {}", map.from, value, map.to, new_value);
-    //                 current_map = &map.to;
-    //                 value = new_value;
-    //             }
-    //         }
-    //     }
-    //     if lowest_location.is_none() || lowest_location.unwrap() > value {
-    //         lowest_location = Some(value);
-    //     }
-    // }
-    //
-    // info!(day=5, part=1, answer=lowest_location.unwrap());
-
-    let mut flat_map = Day5Map::new("seed".to_string(), "seed".to_string(), vec![Range::empty()]);
-    while flat_map.to != dest_map {
-        for map in &maps {
-            if map.from == flat_map.to {
-                flat_map = flat_map.flatten(map);
-            }
-        }
-    }
+        parse_ranges,
+    ))(input).map(|(rest, (from, _, to, _, _, map))|
+        (rest, Day5Map { from: from.to_string(), to: to.to_string(), map })
+    )
+}
 
-    let mut lowest_location = None;
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub flat_map: Day5Map,
+}
 
-    for seed in &seeds {
-        let value = flat_map.range_for(*seed).map_value(*seed).unwrap();
-        if lowest_location.is_none() || lowest_location.unwrap() > value {
-            lowest_location = Some(value);
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+
+",
+        part1: Some("35"),
+        part2: Some("46"),
+    },
+];
+
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Almanac;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let (seeds, _, maps, _) = parse_all(input, tuple((
+            parse_seeds,
+            newline,
+            blocks(parse_map),
+            // The input may end with a blank line after the last map, unless it's been trimmed off
+            opt(newline)
+        )))?;
+
+        debug!(seeds=?seeds, maps=?maps);
+
+        let dest_map = "location";
+
+        // let mut lowest_location = None;
+        //
+        // for seed in &seeds {
+        //     let mut current_map = "seed";
+        //     let mut value = *seed;
+        //     while current_map != dest_map {
+        //         for map in &maps {
+        //             if map.from == current_map {
+        //                 let new_value = map.map.apply(value);
+        //                 debug!("mapping {} {} to {} {}", map.from, value, map.to, new_value);
+        //                 current_map = &map.to;
+        //                 value = new_value;
+        //             }
+        //         }
+        //     }
+        //     if lowest_location.is_none() || lowest_location.unwrap() > value {
+        //         lowest_location = Some(value);
+        //     }
+        // }
+        //
+        // info!(day=5, part=1, answer=lowest_location.unwrap());
+
+        let mut flat_map = Day5Map { from: "seed".to_string(), to: "seed".to_string(), map: ShiftMap::identity() };
+        for steps in 0.. {
+            if flat_map.to == dest_map {
+                break
+            }
+            // Each map can only be used once on the way, or the chain has gone round in a loop
+            if steps == maps.len() {
+                return Err(eyre!("the maps from seed go round in a loop without reaching {dest_map}"))
+            }
+            let map = maps.iter().find(|map| map.from == flat_map.to)
+                .ok_or_else(|| eyre!("no map from {} on the way to {dest_map}", flat_map.to))?;
+            flat_map = flat_map.flatten(map);
         }
-    }
 
-    info!(day=5, part=1, answer=lowest_location.unwrap());
+        Ok(Almanac { seeds, flat_map })
+    }
 
-    lowest_location = None;
+    fn part1(almanac: &Self::Input) -> PartResult {
+        let lowest_location = almanac.seeds.iter().map(|&seed| almanac.flat_map.map.apply(seed)).min()
+            .ok_or_else(|| eyre!("no seeds to find a location for"))?;
 
-    for chunk in seeds.chunks(2) {
-        let &[mut seed, length] = chunk else { panic!("uneven chunks") };
-        let end = seed + length;
-        while seed < end {
-            let range = flat_map.range_for(seed);
-            let value = range.map_value(seed).unwrap();
-            if lowest_location.is_none() || lowest_location.unwrap() > value {
-                lowest_location = Some(value);
-            }
-            // This is the lowest value we're going to get within this range, so jump ahead to the
-            // end of it
-            seed += range.end() - seed;
-        }
+        info!(day=5, part=1, answer=lowest_location);
+        Ok(Some(lowest_location.into()))
     }
 
-    info!(day=5, part=2, answer=lowest_location.unwrap());
+    fn part2(almanac: &Self::Input) -> PartResult {
+        let mut seeds = Vec::new();
+        for chunk in almanac.seeds.chunks(2) {
+            let &[start, length] = chunk else { return Err(eyre!("seeds should come in start and length pairs")) };
+            seeds.push(Interval::with_length(start, length));
+        }
+        let seeds: IntervalSet = seeds.into_iter().collect();
+        let lowest_location = almanac.flat_map.map.image(&seeds).min()
+            .ok_or_else(|| eyre!("the seed ranges are all empty"))?;
 
-    Ok(())
+        info!(day=5, part=2, answer=lowest_location);
+        Ok(Some(lowest_location.into()))
+    }
 }
@@ -1,24 +1,23 @@
 use color_eyre::Report;
-use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, newline, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::separated_list1;
+use nom::character::complete::newline;
 use nom::sequence::tuple;
 use tracing::{debug, info};
 
+use crate::parse::{IResult, field, parse_all, unsigned_numbers};
+use crate::solver::{Example, PartResult, Puzzle};
+
 #[derive(Debug)]
-struct Race {
-    time: i64,
-    distance: i64
+pub struct Race {
+    pub time: i64,
+    pub distance: i64
 }
 
 impl Race {
-    fn distance_traveled(&self, acceleration_time: i64) -> i64 {
+    pub fn distance_traveled(&self, acceleration_time: i64) -> i64 {
         (self.time - acceleration_time) * acceleration_time
     }
 
-    fn win_states(&self) -> i64 {
+    pub fn win_states(&self) -> i64 {
         let mut states = 0;
         for acceleration_time in 0..self.time {
             if self.distance_traveled(acceleration_time) > self.distance {
@@ -29,49 +28,64 @@ impl Race {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Race>> {
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Race>> {
     tuple((
-        tag("Time:"),
-        space1,
-        separated_list1(
-            space1,
-            map_res(digit1, |s: &str| s.parse())
-        ),
+        field("Time", unsigned_numbers),
         newline,
-        tag("Distance:"),
-        space1,
-        separated_list1(
-            space1,
-            map_res(digit1, |s: &str| s.parse())
-        ),
+        field("Distance", unsigned_numbers),
         newline,
-    ))(input).map(|(rest, (_, _, times, _, _, _, distances, _))|
+    ))(input).map(|(rest, (times, _, distances, _))|
         (rest, times.iter().zip(distances.iter()).map(|(&time, &distance)| Race { time, distance }).collect())
     )
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Time:      7  15   30
+Distance:  9  40  200
+",
+        part1: Some("288"),
+        part2: Some("71503"),
+    },
+];
+
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Wait For It";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
 
-    let (_, races) = all_consuming(parse_input)(&input).unwrap();
-    debug!(?races);
+    type Input = Vec<Race>;
 
-    let mut state_product = 1;
-    for race in &races {
-        let states = race.win_states();
-        debug!(?race, states);
-        state_product *= states;
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let races = parse_all(input, parse_input)?;
+        debug!(?races);
+        Ok(races)
     }
 
-    info!(day=6, part=1, answer=state_product);
+    fn part1(races: &Self::Input) -> PartResult {
+        let mut state_product = 1;
+        for race in races {
+            let states = race.win_states();
+            debug!(?race, states);
+            state_product *= states;
+        }
 
-    let part2_time: String = races.iter().map(|r| r.time.to_string()).collect();
-    let part2_distance: String = races.iter().map(|r| r.distance.to_string()).collect();
+        info!(day=6, part=1, answer=state_product);
+        Ok(Some(state_product.into()))
+    }
 
-    let part2_race = Race { time: part2_time.parse().unwrap(), distance: part2_distance.parse().unwrap() };
-    debug!(?part2_race);
-    let win_states = part2_race.win_states();
+    fn part2(races: &Self::Input) -> PartResult {
+        let part2_time: String = races.iter().map(|r| r.time.to_string()).collect();
+        let part2_distance: String = races.iter().map(|r| r.distance.to_string()).collect();
 
-    info!(day=6, part=2, answer=win_states);
+        let part2_race = Race { time: part2_time.parse()?, distance: part2_distance.parse()? };
+        debug!(?part2_race);
+        let win_states = part2_race.win_states();
 
-    Ok(())
+        info!(day=6, part=2, answer=win_states);
+        Ok(Some(win_states.into()))
+    }
 }
@@ -1,22 +1,23 @@
 use std::cmp::Ordering;
 use color_eyre::Report;
-use nom::character::complete::{digit1, newline, one_of, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::{count, many1};
-use nom::sequence::{terminated, tuple};
+use nom::character::complete::space1;
+use nom::multi::count;
+use nom::sequence::tuple;
 use tracing::{debug, info};
 
-#[derive(Debug, Eq, PartialEq)]
-struct Hand {
-    cards: Vec<i8>,
-    bid: i64
+use crate::parse::{IResult, lines, one_of, parse_all, unsigned};
+use crate::solver::{Example, PartResult, Puzzle};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hand {
+    pub cards: Vec<i8>,
+    pub bid: i64
 }
 
-const JOKER_VALUE: i8 = 1;
+pub const JOKER_VALUE: i8 = 1;
 
 impl Hand {
-    fn value_before_jokers(&self) -> Value {
+    pub fn value_before_jokers(&self) -> Value {
         let mut cards = self.cards.clone();
         cards.sort();
         let mut counts = vec![1];
@@ -41,7 +42,7 @@ impl Hand {
         }
     }
 
-    fn value(&self) -> Value {
+    pub fn value(&self) -> Value {
         let jokers = self.cards.iter().filter(|c| **c == JOKER_VALUE).count();
         match (self.value_before_jokers(), jokers) {
             // No jokers means the value doesn't change
@@ -92,7 +93,7 @@ impl PartialOrd for Hand {
 }
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
-enum Value {
+pub enum Value {
     Nothing,
     OnePair,
     TwoPair,
@@ -102,22 +103,22 @@ enum Value {
     FiveOfAKind,
 }
 
-fn parse_card(input: &str) -> IResult<&str, i8> {
+pub fn parse_card(input: &str) -> IResult<&str, i8> {
     one_of("23456789TJQKA")(input)
         .map(|(rest, ch)| (rest, card_to_value(ch)))
 }
 
-fn parse_hand(input: &str) -> IResult<&str, Hand> {
+pub fn parse_hand(input: &str) -> IResult<&str, Hand> {
     tuple((
         count(parse_card, 5),
         space1,
-        map_res(digit1, |s: &str| s.parse())
+        unsigned
     ))(input).map(|(rest, (cards, _, bid))|
         (rest, Hand { cards, bid } )
     )
 }
 
-fn card_to_value(card: char) -> i8 {
+pub fn card_to_value(card: char) -> i8 {
     match card {
         '2' ..= '9' => ((card as u8) - 0x30) as i8,
         'T' => 10,
@@ -129,16 +130,7 @@ fn card_to_value(card: char) -> i8 {
     }
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-
-    let mut hands = all_consuming(
-        many1(
-            terminated(
-                parse_hand,
-                newline
-            ))
-    )(&input).map(|(_, hands)| hands).unwrap();
-
+fn total_winnings(hands: &mut [Hand]) -> i64 {
     hands.sort();
 
     let mut total_score = 0;
@@ -147,26 +139,56 @@ pub(crate) fn solve(input: String) -> Result<(), Report> {
         debug!(hand=?hand, value=?hand.value(), score=?score);
         total_score += score;
     }
+    total_score
+}
 
-    info!(day=7, part=1, answer=total_score);
-
-    for hand in &mut hands {
-        for c in hand.cards.iter_mut() {
-            if *c == 11 {
-                *c = JOKER_VALUE
-            }
-        }
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+",
+        part1: Some("6440"),
+        part2: Some("5905"),
+    },
+];
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "Camel Cards";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<Hand>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        Ok(parse_all(input, lines(parse_hand))?)
     }
 
-    hands.sort();
-    let mut total_score_part2 = 0;
-    for (index, hand) in hands.iter().enumerate() {
-        let score = hand.bid * (index + 1) as i64;
-        debug!(hand=?hand, value=?hand.value(), score=?score);
-        total_score_part2 += score;
+    fn part1(hands: &Self::Input) -> PartResult {
+        let total_score = total_winnings(&mut hands.clone());
+
+        info!(day=7, part=1, answer=total_score);
+        Ok(Some(total_score.into()))
     }
 
-    info!(day=7, part=2, answer=total_score_part2);
+    fn part2(hands: &Self::Input) -> PartResult {
+        let mut hands = hands.clone();
+        for hand in &mut hands {
+            for c in hand.cards.iter_mut() {
+                if *c == 11 {
+                    *c = JOKER_VALUE
+                }
+            }
+        }
 
-    Ok(())
+        let total_score_part2 = total_winnings(&mut hands);
+
+        info!(day=7, part=2, answer=total_score_part2);
+        Ok(Some(total_score_part2.into()))
+    }
 }
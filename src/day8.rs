@@ -1,20 +1,26 @@
+use std::iter::successors;
+
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
 use fnv::FnvHashMap;
-use nom::bytes::complete::{tag, take_while1};
-use nom::character::complete::{newline, one_of};
-use nom::combinator::all_consuming;
-use nom::IResult;
+use nom::bytes::complete::take_while1;
+use nom::character::complete::newline;
 use nom::multi::many1;
 use nom::sequence::{terminated, tuple};
 use tracing::{debug, info};
 
+use crate::cycle::{find_hashed, Cycle};
+use crate::numtheory::crt;
+use crate::parse::{IResult, lines, one_of, parse_all, tag};
+use crate::solver::{Example, PartResult, Puzzle};
+
 #[derive(Debug, Copy, Clone)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
 
-fn parse_direction(input: &str) -> IResult<&str, Direction> {
+pub fn parse_direction(input: &str) -> IResult<&str, Direction> {
     one_of("LR")(input).map(|(rest, ch)|
         (rest,
             match ch {
@@ -26,7 +32,7 @@ fn parse_direction(input: &str) -> IResult<&str, Direction> {
     )
 }
 
-fn parse_node(input: &str) -> IResult<&str, (&str, &str, &str)> {
+pub fn parse_node(input: &str) -> IResult<&str, (&str, &str, &str)> {
     tuple((
         take_while1(|c: char| c.is_alphanumeric()),
         tag(" = ("),
@@ -39,10 +45,8 @@ fn parse_node(input: &str) -> IResult<&str, (&str, &str, &str)> {
     )
 }
 
-fn parse_nodeset(input: &str) -> IResult<&str, FnvHashMap<String, (String, String)>> {
-    many1(
-        terminated(parse_node, newline)
-    )(input).map(|(rest, nodes)| {
+pub fn parse_nodeset(input: &str) -> IResult<&str, FnvHashMap<String, (String, String)>> {
+    lines(parse_node)(input).map(|(rest, nodes)| {
         let mut all_nodes = FnvHashMap::default();
         for (origin, left, right) in nodes {
             all_nodes.insert(origin.to_string(), (left.to_string(), right.to_string()));
@@ -51,7 +55,7 @@ fn parse_nodeset(input: &str) -> IResult<&str, FnvHashMap<String, (String, Strin
     })
 }
 
-fn walk(nodeset: &FnvHashMap<String, (String, String)>, directions: &[Direction], start: &String, is_end: impl Fn(&String) -> bool) -> (String, usize) {
+pub fn walk(nodeset: &FnvHashMap<String, (String, String)>, directions: &[Direction], start: &String, is_end: impl Fn(&String) -> bool) -> (String, usize) {
     let mut steps = directions.iter().cycle();
     let mut position = start;
     let mut steps_taken = 0;
@@ -68,90 +72,189 @@ fn walk(nodeset: &FnvHashMap<String, (String, String)>, directions: &[Direction]
     }
 }
 
-fn prime_factors(n: u64) -> Vec<u64> {
-    let mut n = n;
-    let mut factor = 2;
-    let mut factors = Vec::new();
-    while n > 1 {
-        while n % factor == 0 {
-            factors.push(factor);
-            n /= factor;
-        }
-        factor += 1;
-    }
-    factors
+#[derive(Debug)]
+pub struct Network {
+    pub directions: Vec<Direction>,
+    pub nodeset: FnvHashMap<String, (String, String)>,
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+",
+        part1: Some("2"),
+        part2: None,
+    },
+    Example {
+        input: "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+",
+        part1: Some("6"),
+        part2: None,
+    },
+    Example {
+        input: "\
+LR
 
-    let (directions, nodeset) = all_consuming(tuple((
-        terminated(
-            many1(parse_direction),
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+",
+        part1: None,
+        part2: Some("6"),
+    },
+    Example {
+        // The first ghost reaches 11Z partway through the directions, and is on a Z node at
+        // steps 1, 2, 4, 6, ...
+        input: "\
+LR
+
+11A = (11Z, XXX)
+11Z = (11B, 11Z)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+",
+        part1: None,
+        part2: Some("2"),
+    },
+];
+
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Network;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let (directions, _, nodeset) = parse_all(input, tuple((
+            terminated(
+                many1(parse_direction),
+                newline,
+            ),
             newline,
-        ),
-        newline,
-        parse_nodeset
-    )))(&input).map(|(_, (directions, _, nodeset))| (directions, nodeset)).unwrap();
-
-    debug!(directions=?directions, nodeset=?nodeset);
-
-    if nodeset.contains_key("AAA") {
-        let mut steps = directions.iter().cycle();
-        let mut position = "AAA";
-        let mut steps_taken = 0;
-        while position != "ZZZ" {
-            let node = nodeset.get(position).unwrap();
-            match steps.next().unwrap() {
-                Direction::Left => position = &node.0,
-                Direction::Right => position = &node.1,
+            parse_nodeset
+        )))?;
+
+        for (origin, (left, right)) in &nodeset {
+            for dest in [left, right] {
+                if !nodeset.contains_key(dest) {
+                    return Err(eyre!("node {origin} leads to unknown node {dest}"))
+                }
             }
-            steps_taken += 1;
         }
 
-        info!(day=8, part=1, answer=steps_taken);
-    } else {
-        info!("Skip part1, no 'AAA'")
+        debug!(directions=?directions, nodeset=?nodeset);
+        Ok(Network { directions, nodeset })
     }
 
-    let positions: Vec<&String> = nodeset.keys().filter(|k| k.ends_with('A')).collect();
-    debug!(?positions);
+    fn part1(network: &Self::Input) -> PartResult {
+        let Network { directions, nodeset } = network;
 
-    let mut step_counts = Vec::new();
-
-    for pos in positions {
-        let (end, initial_steps) = walk(&nodeset, &directions, pos, |s| s.ends_with('Z'));
-        let (end2, loop_steps) = walk(&nodeset, &directions, &end, |s| s == &end);
-        assert_eq!(end, end2);
-        debug!(pos, initial_steps, loop_steps);
+        if nodeset.contains_key("AAA") {
+            let mut steps = directions.iter().cycle();
+            let mut position = "AAA";
+            let mut steps_taken = 0;
+            while position != "ZZZ" {
+                let node = nodeset.get(position).unwrap();
+                match steps.next().unwrap() {
+                    Direction::Left => position = &node.0,
+                    Direction::Right => position = &node.1,
+                }
+                steps_taken += 1;
+            }
 
-        // For my input data, all the loop steps turned out to be the same as the initial steps
-        step_counts.push(loop_steps);
+            info!(day=8, part=1, answer=steps_taken);
+            Ok(Some(steps_taken.into()))
+        } else {
+            info!("Skip part1, no 'AAA'");
+            Ok(None)
+        }
     }
 
-    // Figure out the least common multiple of all the step counts
-    // https://en.wikipedia.org/wiki/Least_common_multiple
-    // We have to make a prime factor soup that has as many of each prime factor as any of our
-    // input numbers has.
-
-    let mut prime_factor_soup = Vec::new();
-    for step_count in step_counts {
-        let factors = prime_factors(step_count as u64);
-        for factor in &factors {
-            // How many instances of this factor do we have in the soup?
-            let have = prime_factor_soup.iter().filter(|&pfs| pfs == factor).count();
-            // How many instances of this factor do we need in the soup?  At least as many as our
-            // current step_count has.
-            let need = factors.iter().filter(|&f| f == factor).count();
-            for _ in have..need {
-                // put it in the soup
-                prime_factor_soup.push(*factor);
-            }
+    fn part2(network: &Self::Input) -> PartResult {
+        let Network { directions, nodeset } = network;
+
+        let positions: Vec<&str> = nodeset.keys().filter(|k| k.ends_with('A')).map(String::as_str).collect();
+        debug!(?positions);
+
+        // A ghost's state is its node and where it is in the directions, so it goes round a cycle
+        // of states. Before the cycle it's on a Z node at a few one-off steps; once in the cycle,
+        // it's on a Z node after t steps when t ≡ hit (mod lambda) for one of the hits in the cycle.
+        let step = |&(node, idx): &(&str, usize)| {
+            let (left, right) = &nodeset[node];
+            let next = match directions[idx] {
+                Direction::Left => left.as_str(),
+                Direction::Right => right.as_str(),
+            };
+            (next, (idx + 1) % directions.len())
+        };
+
+        let mut ghosts = Vec::new();
+        for pos in positions {
+            let start = (pos, 0);
+            let cycle = find_hashed(&start, step);
+            let hits: Vec<usize> = successors(Some(start), |state| Some(step(state)))
+                .take(cycle.mu + cycle.lambda)
+                .enumerate()
+                .filter(|(_, (node, _))| node.ends_with('Z'))
+                .map(|(t, _)| t)
+                .collect();
+            debug!(pos, ?cycle, ?hits);
+            ghosts.push((cycle, hits));
+        }
+
+        // Until every ghost is in its cycle, check each step directly
+        let on_z = |(cycle, hits): &(Cycle, Vec<usize>), t: usize| hits.binary_search(&cycle.equivalent_step(t)).is_ok();
+        let all_in_cycle = ghosts.iter().map(|(cycle, _)| cycle.mu).max().unwrap_or(0);
+        if let Some(steps) = (0..all_in_cycle).find(|&t| ghosts.iter().all(|ghost| on_z(ghost, t))) {
+            info!(day=8, part=2, answer=steps);
+            return Ok(Some(steps.into()))
         }
-    }
-    debug!(?prime_factor_soup);
 
-    let product: u64 = prime_factor_soup.into_iter().product();
+        // After that, try every way of picking one hit in each ghost's cycle, and take the earliest
+        // step that lines them all up
+        let mut choices: Vec<Vec<(u128, u128)>> = vec![vec![]];
+        for (cycle, hits) in &ghosts {
+            let residues: Vec<(u128, u128)> = hits.iter()
+                .filter(|&&hit| hit >= cycle.mu)
+                .map(|&hit| ((hit % cycle.lambda) as u128, cycle.lambda as u128))
+                .collect();
+            choices = choices.iter()
+                .flat_map(|chosen| residues.iter().map(move |&residue| [chosen.as_slice(), &[residue]].concat()))
+                .collect();
+        }
+        let start = all_in_cycle as u128;
+        let steps = choices.iter()
+            .filter_map(|congruences| crt(congruences))
+            .map(|(first, period)| if first < start { first + (start - first).div_ceil(period) * period } else { first })
+            .min()
+            .ok_or_else(|| eyre!("the ghosts are never all on Z nodes at once"))?;
 
-    info!(day=8, part=2, answer=product);
-    Ok(())
+        info!(day=8, part=2, answer=steps);
+        Ok(Some(steps.into()))
+    }
 }
@@ -1,66 +1,98 @@
 use std::borrow::Cow;
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
-use nom::bytes::complete::take_while1;
-use nom::character::complete::{newline, space1};
-use nom::combinator::{all_consuming, map_res};
-use nom::IResult;
-use nom::multi::{many1, separated_list1};
-use nom::sequence::terminated;
 use tracing::{debug, info};
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<i64>>> {
-    many1(
-        terminated(
-            separated_list1(
-                space1,
-                map_res(take_while1(|c: char| c.is_ascii_digit() || c == '-'), |s: &str| s.parse())
-            ),
-            newline
-        )
-    )(input)
+use crate::parse::{IResult, lines, numbers, parse_all};
+use crate::solver::{Example, PartResult, Puzzle};
+
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Vec<i64>>> {
+    lines(numbers)(input)
 }
 
-fn difference_of_steps(steps: &[i64]) -> Vec<i64> {
-    steps.windows(2).map(|w| w[1] - w[0]).collect()
+/// The differences between neighbouring readings, or `None` if one doesn't fit in an `i64`
+pub fn difference_of_steps(steps: &[i64]) -> Option<Vec<i64>> {
+    steps.windows(2).map(|w| w[1].checked_sub(w[0])).collect()
 }
 
-fn predict(mut readings: Cow<[i64]>) -> (i64, i64) {
-    let mut sum_of_last = *readings.last().unwrap();
-    let mut firsts = vec![*readings.first().unwrap()];
+/// The next reading after the history and the one before it, as `(next, previous)`
+pub fn predict(mut readings: Cow<[i64]>) -> Result<(i64, i64), Report> {
+    let overflow = || eyre!("the prediction doesn't fit in 64 bits");
+    let (Some(&last), Some(&first)) = (readings.last(), readings.first()) else {
+        return Err(eyre!("no readings to extrapolate from"))
+    };
+    let mut sum_of_last = last;
+    let mut firsts = vec![first];
     loop {
-        readings = Cow::Owned(difference_of_steps(&readings));
+        readings = Cow::Owned(difference_of_steps(&readings).ok_or_else(overflow)?);
         debug!(?readings);
-        sum_of_last += readings.last().unwrap();
-        firsts.push(*readings.first().unwrap());
+        let (Some(&last), Some(&first)) = (readings.last(), readings.first()) else {
+            return Err(eyre!("the differences run out before they're all zero, so there's no telling what comes next"))
+        };
+        sum_of_last = sum_of_last.checked_add(last).ok_or_else(overflow)?;
+        firsts.push(first);
         if readings.iter().all(|d| *d == 0) {
             break
         }
     }
 
-    let mut first = 0;
+    let mut first: i64 = 0;
     debug!(?firsts);
     for i in (0..firsts.len()).rev() {
         // debug!("first = {} - {} ({})", firsts[i], first, firsts[i] - first);
-        first = firsts[i] - first;
+        first = firsts[i].checked_sub(first).ok_or_else(overflow)?;
     }
-    (sum_of_last, first)
+    Ok((sum_of_last, first))
 }
 
-pub(crate) fn solve(input: String) -> Result<(), Report> {
-    let (_, histories) = all_consuming(parse_input)(&input).unwrap();
-    debug!(?histories);
-    let mut part1_sum = 0;
-    let mut part2_sum = 0;
-    for history in histories {
-        debug!(?history);
-        let (part1_prediction, part2_prediction) = predict(Cow::from(history));
-        part1_sum += part1_prediction;
-        part2_sum += part2_prediction;
-        debug!(part1_prediction, part2_prediction);
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+",
+        part1: Some("114"),
+        part2: Some("2"),
+    },
+];
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+    const EXAMPLES: &'static [Example] = EXAMPLES;
+
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input, Report> {
+        let histories = parse_all(input, parse_input)?;
+        debug!(?histories);
+        Ok(histories)
     }
-    info!(day=9, part=1, answer=part1_sum);
 
-    info!(day=9, part=2, answer=part2_sum);
+    fn part1(histories: &Self::Input) -> PartResult {
+        let mut part1_sum: i64 = 0;
+        for (idx, history) in histories.iter().enumerate() {
+            debug!(?history);
+            let (part1_prediction, _) = predict(Cow::from(history)).map_err(|e| eyre!("line {}: {e}", idx + 1))?;
+            part1_sum = part1_sum.checked_add(part1_prediction).ok_or_else(|| eyre!("the sum doesn't fit in 64 bits"))?;
+            debug!(part1_prediction);
+        }
+        info!(day=9, part=1, answer=part1_sum);
+        Ok(Some(part1_sum.into()))
+    }
 
-    Ok(())
+    fn part2(histories: &Self::Input) -> PartResult {
+        let mut part2_sum: i64 = 0;
+        for (idx, history) in histories.iter().enumerate() {
+            debug!(?history);
+            let (_, part2_prediction) = predict(Cow::from(history)).map_err(|e| eyre!("line {}: {e}", idx + 1))?;
+            part2_sum = part2_sum.checked_add(part2_prediction).ok_or_else(|| eyre!("the sum doesn't fit in 64 bits"))?;
+            debug!(part2_prediction);
+        }
+        info!(day=9, part=2, answer=part2_sum);
+        Ok(Some(part2_sum.into()))
+    }
 }
@@ -0,0 +1,259 @@
+use std::fs::{create_dir_all, write};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use color_eyre::Report;
+use tracing::{debug, info};
+
+/// The event year the puzzle inputs are fetched for
+pub const YEAR: u32 = 2023;
+
+/// Downloads puzzle inputs and submits answers with a session token. Only plain `http://` base URLs are supported, so
+/// for the real site point `base_url` at a local proxy that adds TLS.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    host: String,
+    port: u16,
+    path_prefix: String,
+    session: String,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: &str) -> Result<Fetcher, Report> {
+        let Some(rest) = base_url.strip_prefix("http://") else {
+            return Err(eyre!("Can't fetch from {base_url:?}, only http:// base URLs are supported; use --base-url to go through a plain HTTP proxy"))
+        };
+        let (authority, path_prefix) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], rest[slash..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| eyre!("Invalid port in {base_url:?}"))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(eyre!("No host in {base_url:?}"))
+        }
+        if session.is_empty() {
+            return Err(eyre!("Need a session token to fetch inputs"))
+        }
+        Ok(Fetcher { host: host.to_string(), port, path_prefix: path_prefix.to_string(), session: session.to_string() })
+    }
+
+    /// Download the input for `day`
+    pub fn fetch_input(&self, day: u32) -> Result<String, Report> {
+        let (status, body) = self.request("GET", &format!("/{YEAR}/day/{day}/input"), None)?;
+        if status != 200 {
+            let first_line = body.lines().next().unwrap_or("").trim();
+            return Err(eyre!("Fetching day {day} input failed with HTTP status {status}: {first_line}"))
+        }
+        Ok(body)
+    }
+
+    /// Send a request for `path` under the base URL, with `form` as a URL-encoded form body if
+    /// given, and return the response's status code and body
+    pub(crate) fn request(&self, method: &str, path: &str, form: Option<&str>) -> Result<(u16, String), Report> {
+        let path = format!("{}{path}", self.path_prefix);
+        info!("{method} http://{}:{}{path}", self.host, self.port);
+
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+        let mut request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: advent23\r\nConnection: close\r\n",
+            self.host, self.session
+        );
+        if let Some(form) = form {
+            request.push_str(&format!("Content-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n", form.len()));
+        }
+        request.push_str("\r\n");
+        request.push_str(form.unwrap_or(""));
+        stream.write_all(request.as_bytes())?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let (status, body) = parse_response(&response)?;
+        debug!(status, bytes=body.len());
+        Ok((status, body))
+    }
+}
+
+/// Split an HTTP/1.x response into its status code and body, undoing chunked transfer encoding
+fn parse_response(response: &[u8]) -> Result<(u16, String), Report> {
+    let header_end = response.windows(4).position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| eyre!("Truncated HTTP response"))?;
+    let head = std::str::from_utf8(&response[..header_end])?;
+    let mut body = &response[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or("");
+    let status = status_line.split(' ').nth(1).and_then(|code| code.parse().ok())
+        .ok_or_else(|| eyre!("Invalid HTTP status line {status_line:?}"))?;
+
+    let mut chunked = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") && value.eq_ignore_ascii_case("chunked") {
+            chunked = true;
+        } else if name.eq_ignore_ascii_case("content-length") {
+            let length: usize = value.parse().map_err(|_| eyre!("Invalid Content-Length {value:?}"))?;
+            body = body.get(..length).ok_or_else(|| eyre!("HTTP body shorter than its Content-Length"))?;
+        }
+    }
+
+    let body = if chunked { dechunk(body)? } else { body.to_vec() };
+    Ok((status, String::from_utf8(body)?))
+}
+
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, Report> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n").ok_or_else(|| eyre!("Truncated chunk"))?;
+        let size_text = std::str::from_utf8(&body[..line_end])?;
+        let size_text = size_text.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_text, 16).map_err(|_| eyre!("Invalid chunk size {size_text:?}"))?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(out)
+        }
+        out.extend_from_slice(body.get(..size).ok_or_else(|| eyre!("Truncated chunk"))?);
+        body = body.get(size + 2..).unwrap_or(&[]);
+    }
+}
+
+/// The cached input for `day` in `cache_dir`, fetching and caching it first if it isn't there yet.
+/// `fetcher` is only called if it needs fetching, so it's fine for it to fail when there's no
+/// session token or base URL to fetch with.
+pub fn cached_input(cache_dir: &Path, day: u32, fetcher: impl FnOnce() -> Result<Fetcher, Report>) -> Result<PathBuf, Report> {
+    let path = crate::runner::input_path(cache_dir, day);
+    if path.exists() {
+        debug!("{path:?}: using cached input");
+        return Ok(path)
+    }
+
+    let fetcher = fetcher().wrap_err_with(|| format!("{} not found, and can't fetch it", path.display()))?;
+    let input = fetcher.fetch_input(day)?;
+    create_dir_all(cache_dir)?;
+    write(&path, input)?;
+    info!("cached day {day} input in {}", path.display());
+    Ok(path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::net::TcpListener;
+    use std::thread::{spawn, JoinHandle};
+
+    use super::*;
+
+    /// Serve one connection on a local port, replying with `response`. Returns the base URL to
+    /// fetch from and a handle that gives back the request that was received.
+    pub(crate) fn serve_once(response: &str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/prefix", listener.local_addr().unwrap());
+        let response = response.to_string();
+        let handle = spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            // Read up to the end of the headers, then however much body they promise
+            while !request_complete(&request) {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, handle)
+    }
+
+    fn request_complete(request: &[u8]) -> bool {
+        let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else { return false };
+        let head = String::from_utf8_lossy(&request[..header_end]);
+        let length = head.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, value)| value.trim().parse().unwrap());
+        request.len() >= header_end + 4 + length
+    }
+
+    #[test]
+    fn new_parses_base_url() {
+        let fetcher = Fetcher::new("http://localhost:8080/aoc/", "abc").unwrap();
+        assert_eq!((fetcher.host.as_str(), fetcher.port, fetcher.path_prefix.as_str()), ("localhost", 8080, "/aoc"));
+        let fetcher = Fetcher::new("http://example.com", "abc").unwrap();
+        assert_eq!((fetcher.host.as_str(), fetcher.port, fetcher.path_prefix.as_str()), ("example.com", 80, ""));
+    }
+
+    #[test]
+    fn new_rejects_bad_base_urls() {
+        assert!(Fetcher::new("https://adventofcode.com", "abc").is_err());
+        assert!(Fetcher::new("http://:80", "abc").is_err());
+        assert!(Fetcher::new("http://localhost:port", "abc").is_err());
+        assert!(Fetcher::new("http://localhost", "").is_err());
+    }
+
+    #[test]
+    fn fetch_input_with_content_length() {
+        let (base_url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 6\r\n\r\n1 2 3\nignored");
+        let input = Fetcher::new(&base_url, "abc").unwrap().fetch_input(6).unwrap();
+        assert_eq!(input, "1 2 3\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with(&format!("GET /prefix/{YEAR}/day/6/input HTTP/1.1\r\n")), "{request}");
+        assert!(request.contains("\r\nCookie: session=abc\r\n"), "{request}");
+    }
+
+    #[test]
+    fn fetch_input_chunked() {
+        let (base_url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nTime\r\na;ext=1\r\n:  7 15 30\r\n1\r\n\n\r\n0\r\n\r\n"
+        );
+        let input = Fetcher::new(&base_url, "abc").unwrap().fetch_input(6).unwrap();
+        assert_eq!(input, "Time:  7 15 30\n");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn fetch_input_error_status() {
+        let (base_url, server) = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 37\r\n\r\nPlease don't repeatedly request this\n");
+        let err = Fetcher::new(&base_url, "abc").unwrap().fetch_input(26).unwrap_err();
+        assert_eq!(err.to_string(), "Fetching day 26 input failed with HTTP status 404: Please don't repeatedly request this");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn parse_response_rejects_bad_responses() {
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n").is_err());
+        assert!(parse_response(b"garbage\r\n\r\n").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nabc").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nabc").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\nabc\r\n0\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn cached_input_only_fetches_when_missing() {
+        let cache_dir = std::env::temp_dir().join(format!("advent23-fetch-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        let (base_url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nabc\n");
+        let path = cached_input(&cache_dir, 3, || Fetcher::new(&base_url, "abc")).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "abc\n");
+        server.join().unwrap();
+
+        // Now it's cached, the fetcher isn't even built
+        let cached = cached_input(&cache_dir, 3, || Err(eyre!("no session"))).unwrap();
+        assert_eq!(cached, path);
+
+        let err = cached_input(&cache_dir, 4, || Err(eyre!("no session"))).unwrap_err();
+        assert!(err.to_string().ends_with("not found, and can't fetch it"), "{err}");
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
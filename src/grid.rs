@@ -0,0 +1,367 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use crate::parse::{parse_grid, parse_grid_with, parse_grids, Expected, ParseError};
+
+/// A grid position as `(row, column)`, counting from the top left
+pub type Pos = (usize, usize);
+
+/// A rectangular grid of cells, stored row by row
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<u8> {
+    /// Parse one row per line, checking that every cell is one of `allowed`
+    pub fn parse(input: &str, allowed: &'static str) -> Result<Grid<u8>, ParseError> {
+        Ok(Grid::from_rows(parse_grid(input, allowed)?))
+    }
+
+    /// Parse one row per line, allowing any character but whitespace
+    pub fn parse_any(input: &str) -> Result<Grid<u8>, ParseError> {
+        Ok(Grid::from_rows(parse_grid_with(input, |c| !c.is_whitespace(), Expected::Context("a non-space character"))?))
+    }
+
+    /// Parse several grids separated by blank lines
+    pub fn parse_many(input: &str, allowed: &'static str) -> Result<Vec<Grid<u8>>, ParseError> {
+        Ok(parse_grids(input, allowed)?.into_iter().map(Grid::from_rows).collect())
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid from its rows, which must all be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "grid rows must all be the same length");
+        Grid { width, height, cells: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.in_bounds(pos).then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Every cell, row by row
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every position with its cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks(0) panics, and an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// The positions above, below, left and right of `pos` that are inside the grid
+    pub fn neighbours4(&self, (row, col): Pos) -> impl Iterator<Item = Pos> + '_ {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter().filter_map(move |d| self.offset((row, col), d))
+    }
+
+    /// The positions around `pos`, diagonals included, that are inside the grid
+    pub fn neighbours8(&self, (row, col): Pos) -> impl Iterator<Item = Pos> + '_ {
+        [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)].into_iter()
+            .filter_map(move |d| self.offset((row, col), d))
+    }
+
+    /// `pos` moved by `(drow, dcol)`, if that's still inside the grid
+    pub fn offset(&self, (row, col): Pos, (drow, dcol): (isize, isize)) -> Option<Pos> {
+        let pos = (row.checked_add_signed(drow)?, col.checked_add_signed(dcol)?);
+        self.in_bounds(pos).then_some(pos)
+    }
+
+    /// The first position, row by row, whose cell matches `pred`
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    /// Every position whose cell matches `pred`, row by row
+    pub fn find_all<'a>(&'a self, pred: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Pos> + 'a {
+        self.iter().filter(move |(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    /// Breadth-first search from `start`, where `next` gives the positions reachable in one step
+    /// from a position. Returns how many steps it takes to reach each position, or `None` for
+    /// positions that can't be reached.
+    pub fn flood_fill<I: IntoIterator<Item = Pos>>(&self, start: Pos, mut next: impl FnMut(Pos) -> I) -> Grid<Option<usize>> {
+        let mut steps = Grid::new(self.width, self.height, None);
+        steps[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            let step = steps[pos].unwrap_or(0);
+            for next_pos in next(pos) {
+                if steps.get(next_pos).is_some_and(|s| s.is_none()) {
+                    steps[next_pos] = Some(step + 1);
+                    queue.push_back(next_pos);
+                }
+            }
+        }
+        steps
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// The grid flipped about its main diagonal, so rows become columns
+    pub fn transpose(&self) -> Grid<T> {
+        self.rearranged(|(row, col)| (col, row))
+    }
+
+    /// The grid turned a quarter turn clockwise
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        self.rearranged(|(row, col)| (self.height - 1 - col, row))
+    }
+
+    /// The grid turned a quarter turn anticlockwise
+    pub fn rotate_anticlockwise(&self) -> Grid<T> {
+        self.rearranged(|(row, col)| (col, self.width - 1 - row))
+    }
+
+    /// A grid with this one's width and height swapped, whose cell at each position is this
+    /// grid's cell at `source(pos)`
+    fn rearranged(&self, source: impl Fn(Pos) -> Pos) -> Grid<T> {
+        let (width, height) = (self.height, self.width);
+        let cells = (0..height).flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Grid { width, height, cells }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Pos) -> &T {
+        assert!(col < self.width, "column {col} out of bounds for width {}", self.width);
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Pos) -> &mut T {
+        assert!(col < self.width, "column {col} out of bounds for width {}", self.width);
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+/// Grids of bytes display as text, one line per row
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2 rows of 3 columns
+    fn letters() -> Grid<u8> {
+        Grid::parse_any("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let grid = letters();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], b'f');
+        assert_eq!(grid.get((0, 1)), Some(&b'b'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(grid.cells(), b"abcdef");
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn parse_checks_cells_and_row_lengths() {
+        assert!(Grid::parse("#.#\n...\n", ".#").is_ok());
+        let err = Grid::parse("#.#\n.x.\n", ".#").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = Grid::parse("#.#\n..\n", ".#").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        let grids = Grid::parse_many("#.\n.#\n\n###\n", ".#").unwrap();
+        assert_eq!(grids.iter().map(|g| (g.width(), g.height())).collect::<Vec<_>>(), [(2, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn get_mut_and_index_mut() {
+        let mut grid = letters();
+        *grid.get_mut((0, 0)).unwrap() = b'x';
+        grid[(1, 1)] = b'y';
+        assert!(grid.get_mut((2, 0)).is_none());
+        assert_eq!(grid.to_string(), "xbc\ndyf\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 out of bounds for width 3")]
+    fn index_past_the_end_of_a_row() {
+        // Without the check this would quietly wrap round to (1, 0)
+        let _ = letters()[(0, 3)];
+    }
+
+    #[test]
+    fn from_rows() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(2, 0)], 5);
+
+        let empty: Grid<u8> = Grid::from_rows(vec![]);
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.positions().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid rows must all be the same length")]
+    fn from_rows_of_different_lengths() {
+        Grid::from_rows(vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = letters();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        let half_turn = grid.rotate_clockwise().rotate_clockwise();
+        assert_eq!(half_turn.to_string(), "fed\ncba\n");
+        assert_eq!(half_turn.rotate_clockwise().rotate_clockwise(), grid);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = letters();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [b"abc", b"def"]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), b"be");
+        let columns: Vec<Vec<u8>> = grid.columns().map(|column| column.copied().collect()).collect();
+        assert_eq!(columns, [b"ad", b"be", b"cf"]);
+    }
+
+    #[test]
+    fn positions_in_row_order() {
+        let grid = letters();
+        assert_eq!(grid.positions().collect::<Vec<_>>(), [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &b'e')));
+    }
+
+    #[test]
+    fn neighbours_at_corners_and_edges() {
+        let grid = letters();
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 2)).collect::<Vec<_>>(), [(0, 2), (1, 1)]);
+        assert_eq!(grid.neighbours4((0, 1)).collect::<Vec<_>>(), [(1, 1), (0, 0), (0, 2)]);
+
+        assert_eq!(grid.neighbours8((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbours8((1, 2)).collect::<Vec<_>>(), [(0, 1), (0, 2), (1, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).collect::<Vec<_>>(), [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]);
+
+        let big = Grid::new(3, 3, 0);
+        assert_eq!(big.neighbours4((1, 1)).count(), 4);
+        assert_eq!(big.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn offset() {
+        let grid = letters();
+        assert_eq!(grid.offset((1, 1), (-1, 1)), Some((0, 2)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((1, 2), (0, 1)), None);
+        assert_eq!(grid.offset((0, 0), (isize::MIN, 0)), None);
+    }
+
+    #[test]
+    fn find_and_find_all() {
+        let grid = Grid::parse("#..\n.##\n", ".#").unwrap();
+        assert_eq!(grid.find(|&c| c == b'#'), Some((0, 0)));
+        assert_eq!(grid.find(|&c| c == b'S'), None);
+        assert_eq!(grid.find_all(|&c| c == b'#').collect::<Vec<_>>(), [(0, 0), (1, 1), (1, 2)]);
+        assert_eq!(grid.find_all(|&c| c == b'S').count(), 0);
+    }
+
+    #[test]
+    fn flood_fill_leaves_unreachable_cells_empty() {
+        let grid = Grid::parse(".#.\n.#.\n..#\n", ".#").unwrap();
+        let open = |pos| grid.neighbours4(pos).filter(|&next| grid[next] == b'.').collect::<Vec<_>>();
+        let steps = grid.flood_fill((0, 0), open);
+        assert_eq!(steps.rows().collect::<Vec<_>>(), [
+            &[Some(0), None, None],
+            &[Some(1), None, None],
+            &[Some(2), Some(3), None],
+        ]);
+    }
+
+    #[test]
+    fn map() {
+        let grid = letters().map(|&c| c - b'a');
+        assert_eq!(grid.cells(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+    }
+}
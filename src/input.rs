@@ -0,0 +1,49 @@
+use std::fs::read_to_string;
+use std::io::{read_to_string as read_all, stdin};
+use std::path::Path;
+
+use color_eyre::Report;
+use tracing::debug;
+
+/// Read a puzzle input file, or stdin if `path` is `-`. Unless `raw` is set the text is normalized
+/// first, so parsers only ever see `\n`-terminated lines.
+pub fn read_input(path: &Path, raw: bool) -> Result<String, Report> {
+    let input = if path == Path::new("-") {
+        read_all(stdin())?
+    } else {
+        read_to_string(path)?
+    };
+    prepare(&format!("{path:?}"), input, raw)
+}
+
+/// Use text given on the command line as the puzzle input, just as if it had been read from a file
+pub fn input_text(text: &str, raw: bool) -> Result<String, Report> {
+    prepare("--input-text", text.to_string(), raw)
+}
+
+fn prepare(source: &str, input: String, raw: bool) -> Result<String, Report> {
+    debug!("{source}: read {count} bytes", count=input.len());
+
+    if raw {
+        return Ok(input)
+    }
+    let normalized = normalize(&input);
+    if normalized != input {
+        debug!("{source}: normalized to {count} bytes", count=normalized.len());
+    }
+    Ok(normalized)
+}
+
+/// Strip a byte order mark, convert CRLF and CR line endings to LF, strip trailing whitespace
+/// from each line, drop trailing blank lines and end with exactly one newline
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = input.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut normalized = String::with_capacity(input.len() + 1);
+    for line in input.trim_end().lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized
+}
@@ -0,0 +1,416 @@
+use std::fmt::{Display, Formatter};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Report;
+
+/// The half-open interval `start..end`, empty if `end <= start`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// Everything a `ShiftMap` can map, which is every `i64` but `i64::MAX`
+    pub const ALL: Interval = Interval { start: i64::MIN, end: i64::MAX };
+
+    pub fn new(start: i64, end: i64) -> Interval {
+        Interval { start, end }
+    }
+
+    /// The interval of `length` values from `start`
+    pub fn with_length(start: i64, length: i64) -> Interval {
+        Interval { start, end: start + length }
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The overlap of the two intervals, if they overlap at all
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let overlap = Interval { start: self.start.max(other.start), end: self.end.min(other.end) };
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    pub fn shifted(&self, shift: i64) -> Interval {
+        Interval { start: self.start + shift, end: self.end + shift }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of values kept as sorted intervals that don't overlap or touch
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// The intervals, in order
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many values are in the set
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|i| i.end - 1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(idx).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        *self = self.union(&IntervalSet::from_iter([interval]));
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals.iter().chain(&other.intervals).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while let (Some(x), Some(y)) = (self.intervals.get(a), other.intervals.get(b)) {
+            intervals.extend(x.intersection(y));
+            if x.end < y.end { a += 1 } else { b += 1 }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The values in this set that aren't in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            while let Some(cut) = others.peek() {
+                if cut.end <= start {
+                    others.next();
+                    continue
+                }
+                if cut.start >= interval.end {
+                    break
+                }
+                if cut.start > start {
+                    intervals.push(Interval::new(start, cut.start));
+                }
+                start = cut.end;
+                if cut.end > interval.end {
+                    break
+                }
+                others.next();
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    /// The union of the intervals, which can be in any order and overlap
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> IntervalSet {
+        let mut sorted: Vec<Interval> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        sorted.sort();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+/// One piece of a `ShiftMap`: values in `interval` map to themselves plus `shift`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Shift {
+    pub interval: Interval,
+    pub shift: i64,
+}
+
+/// A piecewise translation: each piece shifts its values by a fixed amount, and values outside
+/// every piece map to themselves
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ShiftMap {
+    /// Sorted and non-overlapping, with no empty pieces and no zero shifts
+    pieces: Vec<Shift>,
+}
+
+impl ShiftMap {
+    /// The map that leaves every value where it is
+    pub fn identity() -> ShiftMap {
+        ShiftMap::default()
+    }
+
+    /// A map from pieces in any order, which mustn't overlap
+    pub fn new(pieces: impl IntoIterator<Item = Shift>) -> Result<ShiftMap, Report> {
+        let mut pieces: Vec<Shift> = pieces.into_iter()
+            .filter(|p| !p.interval.is_empty() && p.shift != 0)
+            .collect();
+        pieces.sort_by_key(|p| p.interval.start);
+        if let Some(pair) = pieces.windows(2).find(|pair| pair[0].interval.end > pair[1].interval.start) {
+            return Err(eyre!("ranges {} and {} overlap", pair[0].interval, pair[1].interval))
+        }
+        Ok(ShiftMap::from_sorted(pieces))
+    }
+
+    /// A map from sorted, non-overlapping pieces, merging touching pieces with the same shift
+    fn from_sorted(pieces: impl IntoIterator<Item = Shift>) -> ShiftMap {
+        let mut merged: Vec<Shift> = Vec::new();
+        for piece in pieces {
+            if piece.interval.is_empty() || piece.shift == 0 {
+                continue
+            }
+            match merged.last_mut() {
+                Some(last) if last.shift == piece.shift && last.interval.end == piece.interval.start => last.interval.end = piece.interval.end,
+                _ => merged.push(piece),
+            }
+        }
+        ShiftMap { pieces: merged }
+    }
+
+    /// The pieces that move values, in order. Values between them map to themselves.
+    pub fn pieces(&self) -> &[Shift] {
+        &self.pieces
+    }
+
+    /// The piece that `value` falls in, if any
+    pub fn piece_for(&self, value: i64) -> Option<&Shift> {
+        let idx = self.pieces.partition_point(|p| p.interval.end <= value);
+        self.pieces.get(idx).filter(|p| p.interval.contains(value))
+    }
+
+    pub fn shift_for(&self, value: i64) -> i64 {
+        self.piece_for(value).map_or(0, |p| p.shift)
+    }
+
+    pub fn apply(&self, value: i64) -> i64 {
+        value + self.shift_for(value)
+    }
+
+    /// `interval` split where the map's shift changes, with the shift for each part, in order
+    pub fn segments(&self, interval: Interval) -> Vec<Shift> {
+        let mut segments = Vec::new();
+        let mut start = interval.start;
+        let first = self.pieces.partition_point(|p| p.interval.end <= start);
+        for piece in &self.pieces[first..] {
+            if start >= interval.end || piece.interval.start >= interval.end {
+                break
+            }
+            if piece.interval.start > start {
+                segments.push(Shift { interval: Interval::new(start, piece.interval.start), shift: 0 });
+                start = piece.interval.start;
+            }
+            let end = piece.interval.end.min(interval.end);
+            segments.push(Shift { interval: Interval::new(start, end), shift: piece.shift });
+            start = end;
+        }
+        if start < interval.end {
+            segments.push(Shift { interval: Interval::new(start, interval.end), shift: 0 });
+        }
+        segments
+    }
+
+    /// The map that applies this one and then `then`
+    pub fn compose(&self, then: &ShiftMap) -> ShiftMap {
+        let mut pieces = Vec::new();
+        for first in self.segments(Interval::ALL) {
+            for second in then.segments(first.interval.shifted(first.shift)) {
+                pieces.push(Shift { interval: second.interval.shifted(-first.shift), shift: first.shift + second.shift });
+            }
+        }
+        ShiftMap::from_sorted(pieces)
+    }
+
+    /// The map that undoes this one, or `None` if two values map to the same place, so that it
+    /// can't be undone
+    pub fn invert(&self) -> Option<ShiftMap> {
+        let mut images: Vec<Shift> = self.segments(Interval::ALL).into_iter()
+            .map(|s| Shift { interval: s.interval.shifted(s.shift), shift: -s.shift })
+            .collect();
+        images.sort_by_key(|s| s.interval.start);
+        // A bijection of `Interval::ALL` onto itself has to tile it exactly
+        let mut next = Interval::ALL.start;
+        for image in &images {
+            if image.interval.start != next {
+                return None
+            }
+            next = image.interval.end;
+        }
+        (next == Interval::ALL.end).then(|| ShiftMap::from_sorted(images))
+    }
+
+    /// Where the values in `set` end up
+    pub fn image(&self, set: &IntervalSet) -> IntervalSet {
+        set.intervals().iter()
+            .flat_map(|&interval| self.segments(interval))
+            .map(|s| s.interval.shifted(s.shift))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    fn values(set: &IntervalSet) -> BTreeSet<i64> {
+        set.intervals().iter().flat_map(|i| i.start..i.end).collect()
+    }
+
+    fn shifts(pieces: &[(i64, i64, i64)]) -> ShiftMap {
+        ShiftMap::new(pieces.iter().map(|&(start, end, shift)| Shift { interval: Interval::new(start, end), shift })).unwrap()
+    }
+
+    #[test]
+    fn set_merges_overlapping_and_touching_intervals() {
+        let s = set(&[(10, 12), (0, 3), (2, 5), (5, 7), (20, 20)]);
+        assert_eq!(s.intervals(), &[Interval::new(0, 7), Interval::new(10, 12)]);
+        assert_eq!(s.len(), 9);
+        assert_eq!((s.min(), s.max()), (Some(0), Some(11)));
+        assert!(s.contains(0) && s.contains(6) && s.contains(11));
+        assert!(!s.contains(-1) && !s.contains(7) && !s.contains(12));
+        assert!(set(&[(3, 3)]).is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30), (40, 50)]);
+        let b = set(&[(5, 25), (30, 35), (45, 46), (60, 70)]);
+        assert_eq!(a.union(&b), set(&[(0, 35), (40, 50), (60, 70)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (45, 46)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30), (40, 45), (46, 50)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 35), (60, 70)]));
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+        assert_eq!(a.difference(&set(&[(-5, 100)])), IntervalSet::new());
+        assert_eq!(a.intersection(&IntervalSet::new()), IntervalSet::new());
+    }
+
+    #[test]
+    fn set_operations_match_brute_force() {
+        let sets = [
+            set(&[]),
+            set(&[(0, 10)]),
+            set(&[(3, 4), (6, 9)]),
+            set(&[(-2, 1), (4, 6), (9, 12)]),
+            set(&[(1, 2), (3, 4), (5, 6), (7, 8)]),
+            set(&[(0, 3), (7, 10)]),
+        ];
+        for a in &sets {
+            for b in &sets {
+                let (va, vb) = (values(a), values(b));
+                assert_eq!(values(&a.union(b)), &va | &vb, "{a:?} ∪ {b:?}");
+                assert_eq!(values(&a.intersection(b)), &va & &vb, "{a:?} ∩ {b:?}");
+                assert_eq!(values(&a.difference(b)), &va - &vb, "{a:?} - {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn shift_map_apply() {
+        let map = shifts(&[(98, 100, -48), (50, 98, 2)]);
+        assert_eq!(map.apply(0), 0);
+        assert_eq!(map.apply(50), 52);
+        assert_eq!(map.apply(97), 99);
+        assert_eq!(map.apply(98), 50);
+        assert_eq!(map.apply(100), 100);
+        assert_eq!(map.piece_for(99), Some(&Shift { interval: Interval::new(98, 100), shift: -48 }));
+        assert_eq!(map.piece_for(49), None);
+        assert_eq!(map.shift_for(100), 0);
+    }
+
+    #[test]
+    fn shift_map_rejects_overlapping_pieces() {
+        let pieces = [Shift { interval: Interval::new(0, 5), shift: 1 }, Shift { interval: Interval::new(4, 8), shift: 2 }];
+        assert!(ShiftMap::new(pieces).is_err());
+    }
+
+    #[test]
+    fn shift_map_compose() {
+        let first = shifts(&[(98, 100, -48), (50, 98, 2)]);
+        let then = shifts(&[(15, 52, -15), (52, 54, -15), (0, 15, 39)]);
+        let composed = first.compose(&then);
+        for value in -10..120 {
+            assert_eq!(composed.apply(value), then.apply(first.apply(value)), "{value}");
+        }
+        assert_eq!(first.compose(&ShiftMap::identity()), first);
+        assert_eq!(ShiftMap::identity().compose(&first), first);
+    }
+
+    #[test]
+    fn shift_map_compose_cancels_out() {
+        let there = shifts(&[(0, 10, 5)]);
+        let back = shifts(&[(5, 15, -5)]);
+        assert_eq!(there.compose(&back), shifts(&[(10, 15, -5)]));
+    }
+
+    #[test]
+    fn shift_map_invert() {
+        // Swap 0..10 with 20..30
+        let swap = shifts(&[(0, 10, 20), (20, 30, -20)]);
+        let inverse = swap.invert().unwrap();
+        for value in -5..40 {
+            assert_eq!(inverse.apply(swap.apply(value)), value, "{value}");
+        }
+        assert_eq!(swap.compose(&inverse), ShiftMap::identity());
+        assert_eq!(ShiftMap::identity().invert(), Some(ShiftMap::identity()));
+    }
+
+    #[test]
+    fn shift_map_invert_not_invertible() {
+        // 0..10 lands on 5..15, on top of the values 10..15 that stay where they are
+        assert_eq!(shifts(&[(0, 10, 5)]).invert(), None);
+        // 10..20 moves down onto 0..10, leaving nothing to map to 10..20
+        assert_eq!(shifts(&[(10, 20, -10)]).invert(), None);
+    }
+
+    #[test]
+    fn shift_map_image() {
+        let map = shifts(&[(98, 100, -48), (50, 98, 2)]);
+        assert_eq!(map.image(&set(&[(79, 93), (55, 68)])), set(&[(57, 70), (81, 95)]));
+        assert_eq!(map.image(&set(&[(40, 60), (97, 105)])), set(&[(40, 50), (50, 52), (52, 62), (99, 100), (100, 105)]));
+        assert_eq!(map.image(&IntervalSet::new()), IntervalSet::new());
+        let input = set(&[(-3, 7), (45, 110)]);
+        let expected: BTreeSet<i64> = values(&input).iter().map(|&v| map.apply(v)).collect();
+        assert_eq!(values(&map.image(&input)), expected);
+    }
+}
@@ -0,0 +1,41 @@
+//! Solutions to Advent of Code 2023.
+//!
+//! Every day lives in its own `dayN` module, which exposes its parsers and puzzle types along with
+//! a `DayN` [`Puzzle`](solver::Puzzle). The [`solver`] registry finds a day's solver by number, and
+//! the other modules run, time and check solvers.
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod answer;
+pub mod bench;
+pub mod cycle;
+pub mod fetch;
+pub mod grid;
+pub mod input;
+pub mod intervals;
+pub mod numtheory;
+pub mod parse;
+pub mod render;
+pub mod report;
+pub mod results;
+pub mod runner;
+pub mod scaffold;
+pub mod selftest;
+pub mod solver;
+pub mod submit;
+pub mod timing;
+pub mod verify;
+pub mod watch;
@@ -1,84 +1,368 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-
-use std::fs::read_to_string;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use color_eyre::config::HookBuilder;
+use color_eyre::eyre::eyre;
 use color_eyre::Report;
-use clap::Parser;
-use tracing::debug;
-use tracing_subscriber::EnvFilter;
+use clap::{Parser, Subcommand};
+use tracing::warn;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use advent23::fetch::{self, Fetcher};
+use advent23::input::{input_text, read_input};
+use advent23::report::{self, OutputFormat};
+use advent23::runner::{self, DaySelection, RunStatus};
+use advent23::verify::{self, ExpectedAnswers};
+use advent23::submit::{self, Submissions};
+use advent23::render::{self, Format};
+use advent23::results::{self, ResultsLayer};
+use advent23::timing::{self, TimingLayer};
+use advent23::{bench, scaffold, selftest, solver, watch};
 
 #[derive(Parser, Debug)]
-#[command()]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
-    #[arg(short, long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Log at debug level and show full backtraces
+    #[arg(short, long, global = true)]
     debug: bool,
 
+    /// Log more: -v shows each day's log messages, -vv debug messages and -vvv everything.
+    /// RUST_LOG overrides this.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Log everything from these days (e.g. `10,14`) whatever the verbosity
+    #[arg(long, global = true)]
+    trace: Option<DaySelection>,
+
+    /// List the registered puzzles and exit
     #[arg(short, long)]
-    puzzle: u32,
+    list: bool,
+
+    #[arg(short, long, required_unless_present_any = ["list", "all", "days"])]
+    puzzle: Option<u32>,
 
+    /// Input file, or `-` to read the input from stdin. Without it the day's input is taken from
+    /// the inputs directory, fetching it there first if need be.
     #[arg(short, long)]
-    input: PathBuf
+    input: Option<PathBuf>,
+
+    /// Use this text as the input instead of reading a file
+    #[arg(long, conflicts_with = "input")]
+    input_text: Option<String>,
+
+    /// Run every registered puzzle against the inputs directory
+    #[arg(short, long, conflicts_with_all = ["puzzle", "input", "input_text", "days"])]
+    all: bool,
+
+    /// Run the selected puzzles (e.g. `1-5,8,12`) against the inputs directory
+    #[arg(long, conflicts_with_all = ["puzzle", "input", "input_text"])]
+    days: Option<DaySelection>,
+
+    /// Directory holding inputs named like `day05.txt`, for --all and --days, and where fetched
+    /// inputs are cached
+    #[arg(long, default_value = "inputs")]
+    inputs: PathBuf,
+
+    /// Only solve this part of the puzzle
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+
+    /// Base URL to fetch inputs from and submit answers to. Only plain http:// is supported, so for
+    /// the real site this has to be a local proxy that adds TLS.
+    #[arg(long, global = true, env = "AOC_BASE_URL")]
+    base_url: Option<String>,
+
+    /// Session token sent when fetching inputs and submitting answers
+    #[arg(long, global = true, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+
+    /// File recording every submitted answer and its outcome
+    #[arg(long, global = true, default_value = "submissions.txt")]
+    submissions: PathBuf,
+
+    /// Print results as text or as a single JSON document. JSON is only for solving puzzles, not
+    /// for --list, --watch or the subcommands.
+    #[arg(long, global = true, default_value = "text")]
+    format: OutputFormat,
+
+    /// Time the parse and part phases and the routines inside them, and print a breakdown at the end
+    #[arg(long, global = true)]
+    timings: bool,
+
+    /// Draw the puzzles' grids as they're solved, as ansi (the default), plain, html or ppm. Text
+    /// goes to stderr, and html and ppm are written to files in --render-dir. NO_COLOR turns ansi
+    /// into plain.
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "ansi")]
+    render: Option<Format>,
+
+    /// Directory for html and ppm renderings
+    #[arg(long, global = true, default_value = "renders")]
+    render_dir: PathBuf,
+
+    /// Keep running, and solve the input again whenever the file changes
+    #[arg(short, long, conflicts_with_all = ["input_text", "record", "list", "all", "days"])]
+    watch: bool,
+
+    /// Record this run's answers in the answers file as the expected answers
+    #[arg(long, requires = "puzzle", conflicts_with = "input_text")]
+    record: bool,
+
+    /// Pass input files to the solvers exactly as read, without normalizing line endings, a byte
+    /// order mark, trailing whitespace or the final newline
+    #[arg(long, global = true)]
+    raw: bool,
+
+    /// File of expected answers, for --record and verify
+    #[arg(long, global = true, default_value = "answers.txt")]
+    answers: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time each phase of a puzzle over repeated runs and print the statistics as CSV
+    Bench {
+        #[arg(short, long)]
+        puzzle: u32,
+
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Number of timed runs
+        #[arg(short, long, default_value_t = 10)]
+        runs: u32,
+
+        /// Number of untimed runs before timing starts
+        #[arg(short, long, default_value_t = 1)]
+        warmup: u32,
+    },
+    /// Solve one part of a puzzle and submit the answer, unless earlier submissions rule it out
+    Submit {
+        #[arg(short, long)]
+        puzzle: u32,
+
+        /// Input file; without it the day's input comes from the inputs directory
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Check every answer in the answers file against what the solvers produce now
+    Verify,
+    /// Run every puzzle against the worked examples from its puzzle text
+    Selftest,
+    /// Start a new day: create its module from the template, empty example and input files, and
+    /// register it with the solvers
+    NewDay {
+        day: u32,
+
+        /// The puzzle's title
+        #[arg(short, long, default_value = "")]
+        title: String,
+    },
 }
 
-fn set_up_logging(debug: bool) -> Result<(), Report> {
+/// Log filter directives for a verbosity level. Without -v the days only log warnings, so a normal
+/// run shows just the answers; the days in `trace` log everything.
+fn log_filter(verbosity: u8, trace: Option<&DaySelection>) -> String {
+    let (base, days) = match verbosity {
+        0 => ("info", "warn"),
+        1 => ("info", "info"),
+        2 => ("debug", "debug"),
+        _ => ("trace", "trace"),
+    };
+    let mut directives = vec![base.to_string()];
+    // Every day gets its own directive, as `advent23::day1` would otherwise also match day 10 on
+    for solver in solver::all() {
+        let day = solver.day();
+        let level = if trace.is_some_and(|t| t.days().contains(&day)) { "trace" } else { days };
+        directives.push(format!("advent23::day{day}={level}"));
+    }
+    directives.join(",")
+}
+
+fn set_up_logging(debug: bool, verbosity: u8, trace: Option<&DaySelection>, timings: bool) -> Result<(), Report> {
     if std::env::var("RUST_LIB_BACKTRACE").is_err() && debug {
         std::env::set_var("RUST_LIB_BACKTRACE", "full" );
     }
 
-    color_eyre::install()?;
+    // Span traces would need every span kept alive just in case an error is reported inside it
+    HookBuilder::new().capture_span_trace_by_default(false).install()?;
 
     if std::env::var("RUST_LOG").is_err() {
-        std::env::set_var("RUST_LOG", if debug { "debug" } else { "info" });
+        let verbosity = if debug { verbosity.max(2) } else { verbosity };
+        std::env::set_var("RUST_LOG", log_filter(verbosity, trace));
     }
 
-    tracing_subscriber::fmt::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
+    // The results layer sees answers even when they're filtered out of the log
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(EnvFilter::from_default_env()))
+        .with(ResultsLayer::filtered())
+        // Always installed: an absent layer would enable every span, and make them all cost time
+        .with(TimingLayer.with_filter(filter_fn(move |meta| timings && meta.is_span())))
         .init();
 
     Ok(())
 }
 
+/// A fetcher for the base URL and session token given on the command line or in the environment
+fn fetcher(base_url: Option<&str>, session: Option<&str>) -> Result<Fetcher, Report> {
+    let base_url = base_url.ok_or_else(|| eyre!("Need --base-url or AOC_BASE_URL to fetch inputs or submit answers"))?;
+    let session = session.ok_or_else(|| eyre!("Need --session or AOC_SESSION to fetch inputs or submit answers"))?;
+    Fetcher::new(base_url, session)
+}
+
 fn main() -> Result<(), Report> {
     let args = Args::parse();
-    set_up_logging(args.debug)?;
-
-    let input = read_to_string(&args.input)?;
-
-    debug!("{file:?}: read {count} bytes", file=args.input, count=input.len());
-
-    match args.puzzle {
-        1 => day1::solve(input),
-        2 => day2::solve(input),
-        3 => day3::solve(input),
-        4 => day4::solve(input),
-        5 => day5::solve(input),
-        6 => day6::solve(input),
-        7 => day7::solve(input),
-        8 => day8::solve(input),
-        9 => day9::solve(input),
-        10 => day10::solve(input),
-        11 => day11::solve(input),
-        12 => day12::solve(input),
-        13 => day13::solve(input),
-        14 => day14::solve(input),
-        15 => day15::solve(input),
-        _ => panic!("No such puzzle: {day}", day=args.puzzle),
-    }.unwrap();
+    set_up_logging(args.debug, args.verbose, args.trace.as_ref(), args.timings)?;
+
+    let timings = args.timings;
+    let result = run(args);
+    if timings {
+        timing::print_report();
+    }
+    result
+}
+
+fn run(args: Args) -> Result<(), Report> {
+    if let Some(format) = args.render {
+        render::enable(format, args.render_dir.clone());
+    }
+
+    // Only solving puzzles prints results that can be JSON; everything else would quietly ignore it
+    let text_only = match args.command {
+        Some(Command::Bench { .. }) => Some("bench"),
+        Some(Command::Submit { .. }) => Some("submit"),
+        Some(Command::Verify) => Some("verify"),
+        Some(Command::Selftest) => Some("selftest"),
+        Some(Command::NewDay { .. }) => Some("new-day"),
+        None if args.list => Some("--list"),
+        None if args.watch => Some("--watch"),
+        None => None,
+    };
+    if let Some(mode) = text_only.filter(|_| args.format == OutputFormat::Json) {
+        return Err(eyre!("--format json isn't supported with {mode}, only when solving puzzles"))
+    }
+
+    match args.command {
+        Some(Command::Bench { puzzle, input, runs, warmup }) => {
+            let solver = solver::find(puzzle).ok_or_else(|| eyre!("No such puzzle: {puzzle}"))?;
+            let input = read_input(&input, args.raw)?;
+            return bench::bench(solver, &input, args.part, warmup, runs)
+        }
+        Some(Command::Submit { puzzle, input }) => {
+            let part = args.part.ok_or_else(|| eyre!("Choose the part to submit with --part"))?;
+            let solver = solver::find(puzzle).ok_or_else(|| eyre!("No such puzzle: {puzzle}"))?;
+            let fetcher = fetcher(args.base_url.as_deref(), args.session.as_deref())?;
+            let input_path = match input {
+                Some(input) => input,
+                None => fetch::cached_input(&args.inputs, puzzle, || Ok(fetcher.clone()))?,
+            };
+
+            let answers = solver.solve(read_input(&input_path, args.raw)?, Some(part))?;
+            let (_, answer) = answers.iter().find(|(p, _)| *p == part)
+                .ok_or_else(|| eyre!("Day {puzzle} part {part} gave no answer to submit"))?;
+
+            let mut submissions = Submissions::load(&args.submissions)?;
+            let outcome = submit::submit(&fetcher, &mut submissions, puzzle, part, &answer.to_string())?;
+            submissions.save(&args.submissions)?;
+            println!("Day {puzzle} part {part}: {answer} is {outcome}");
+            return Ok(())
+        }
+        Some(Command::Verify) => {
+            let expected = ExpectedAnswers::load(&args.answers)?;
+            return match verify::verify(&expected, args.part, args.raw) {
+                0 => Ok(()),
+                mismatches => Err(eyre!("{mismatches} answer(s) did not match {file:?}", file=args.answers)),
+            }
+        }
+        Some(Command::Selftest) => {
+            return match selftest::selftest(solver::all(), args.part) {
+                0 => Ok(()),
+                failures => Err(eyre!("{failures} example(s) failed")),
+            }
+        }
+        Some(Command::NewDay { day, title }) => {
+            return scaffold::new_day(Path::new("."), &args.inputs, day, &title)
+        }
+        None => {}
+    }
+
+    if args.list {
+        for solver in solver::all() {
+            println!("{day:>2}  {title} ({parts} parts)", day=solver.day(), title=solver.title(), parts=solver.parts());
+        }
+        return Ok(())
+    }
+
+    if let Some(selection) = args.days.or(args.all.then(|| DaySelection::all(solver::all()))) {
+        let runs = runner::run_all(&selection, &args.inputs, args.part, args.raw);
+        match args.format {
+            OutputFormat::Text => runner::print_summary(&runs),
+            OutputFormat::Json => println!("{}", report::json(&runs)),
+        }
+        return Ok(())
+    }
+
+    let Some(day) = args.puzzle else {
+        unreachable!("clap requires --puzzle without --list, --all or --days")
+    };
+
+    let solver = solver::find(day).ok_or_else(|| eyre!("No such puzzle: {day}"))?;
+
+    let input_path = match (args.input, &args.input_text) {
+        (Some(input_path), _) => Some(input_path),
+        (None, Some(_)) => None,
+        (None, None) => Some(fetch::cached_input(&args.inputs, day, || fetcher(args.base_url.as_deref(), args.session.as_deref()))?),
+    };
+    if let Some(input_path) = input_path.as_ref().filter(|_| args.watch) {
+        if input_path == Path::new("-") {
+            return Err(eyre!("Can't watch input read from stdin"))
+        }
+        return watch::watch(solver, input_path, args.part, args.raw, Duration::from_millis(500))
+    }
+
+    let input = match (&input_path, &args.input_text) {
+        (Some(input_path), _) => read_input(input_path, args.raw)?,
+        (None, Some(text)) => input_text(text, args.raw)?,
+        (None, None) => unreachable!("input_path is set unless there's --input-text"),
+    };
+
+    let answers = match args.format {
+        OutputFormat::Text => {
+            let answers = solver.solve(input, args.part)?;
+            for problem in results::check(day, &answers) {
+                warn!("{problem}");
+            }
+            for (part, answer) in answers.iter() {
+                println!("Day {day} part {part}: {answer}");
+            }
+            answers
+        }
+        OutputFormat::Json => {
+            let run = runner::solve_input(solver, input_path.as_deref(), input, args.part);
+            println!("{}", report::json(std::slice::from_ref(&run)));
+            match run.status {
+                RunStatus::Solved { answers, .. } => answers,
+                RunStatus::Failed(error) | RunStatus::Skipped(error) => return Err(eyre!(error)),
+            }
+        }
+    };
+
+    if let Some(input_path) = input_path.filter(|_| args.record) {
+        if input_path == Path::new("-") {
+            return Err(eyre!("Can't record answers for input read from stdin"))
+        }
+        let mut expected = ExpectedAnswers::load(&args.answers)?;
+        expected.record(day, &input_path, &answers);
+        expected.save(&args.answers)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,234 @@
+use std::fmt::Debug;
+use std::ops::{Div, Rem, Sub};
+
+/// The unsigned integer types the functions here work over
+pub trait Unsigned: Copy + Debug + Eq + Ord + Div<Output = Self> + Rem<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {$(
+        impl Unsigned for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+unsigned!(u64, u128);
+
+/// Greatest common divisor, with `gcd(0, 0) == 0`
+pub fn gcd<T: Unsigned>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it doesn't fit in `T`. `lcm(0, n) == 0`.
+pub fn lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO)
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all of `values`, 1 if there are none, or `None` if it doesn't fit in `T`
+pub fn lcm_all<T: Unsigned>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// The extended Euclidean algorithm: `(g, x, y)` where `g` is the gcd of `a` and `b` and
+/// `a * x + b * y == g`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a + b` modulo `m`, for `a` and `b` already below `m`, without overflowing
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b { a - (m - b) } else { a + b }
+}
+
+/// `a * b` modulo `m`, without overflowing however big `m` is
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m
+    }
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    product
+}
+
+/// The `x` below `m` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m` aren't coprime
+pub fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    if m == 0 {
+        return None
+    }
+    if m == 1 {
+        return Some(0)
+    }
+    // Extended Euclid keeping the coefficient of `a` modulo `m`, so nothing goes negative
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_x, mut x) = (1, 0);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, add_mod(old_x, m - mul_mod(q, x, m), m) % m);
+    }
+    (old_r == 1).then_some(old_x)
+}
+
+/// Solve a system of congruences `x ≡ residue (mod modulus)` with the Chinese Remainder Theorem.
+/// The moduli don't need to be coprime. Returns `(x, l)` where `l` is the lcm of the moduli and
+/// `x < l` is the smallest solution, so every solution is `x + k * l`. Returns `None` if the
+/// congruences contradict each other, a modulus is zero, or `l` doesn't fit in a `u128`.
+pub fn crt(congruences: &[(u128, u128)]) -> Option<(u128, u128)> {
+    let mut solution = (0, 1);
+    for &(residue, modulus) in congruences {
+        solution = crt_pair(solution, (residue, modulus))?;
+    }
+    Some(solution)
+}
+
+fn crt_pair((a1, m1): (u128, u128), (a2, m2): (u128, u128)) -> Option<(u128, u128)> {
+    if m2 == 0 {
+        return None
+    }
+    let a2 = a2 % m2;
+    let g = gcd(m1, m2);
+    // x = a1 + m1 * k, and we need m1 * k ≡ a2 - a1 (mod m2)
+    let diff = if a2 >= a1 % m2 { a2 - a1 % m2 } else { m2 - (a1 % m2 - a2) };
+    if diff % g != 0 {
+        return None
+    }
+    let (m1g, m2g) = (m1 / g, m2 / g);
+    let k = mul_mod(diff / g, mod_inverse(m1g, m2g)?, m2g);
+    let l = m1g.checked_mul(m2)?;
+    Some((add_mod(a1 % l, mul_mod(m1, k, l), l), l))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u64, 7), 7);
+        assert_eq!(gcd(0u64, 0), 0);
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, 2), None);
+        assert_eq!(lcm(u64::MAX as u128, 2), Some(u64::MAX as u128 * 2));
+        assert_eq!(lcm_all([2u128, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(Vec::<u64>::new()), Some(1));
+        assert_eq!(lcm_all([1u64 << 40, 3 << 30, 5 << 20]), Some(15 << 40));
+        assert_eq!(lcm_all([2, u64::MAX - 1, u64::MAX]), None);
+    }
+
+    #[test]
+    fn extended_gcd_finds_bezout_coefficients() {
+        for (a, b) in [(240, 46), (46, 240), (17, 5), (-12, 18), (0, 9), (9, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i128);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_small() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(10, 11), Some(10));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(5, 0), None);
+    }
+
+    #[test]
+    fn mod_inverse_near_u128_max() {
+        // u128::MAX is odd, so 2 is invertible modulo it, but not modulo u128::MAX - 1
+        for (a, m) in [(2, u128::MAX), (u128::MAX - 1, u128::MAX), (3, u128::MAX - 1), (u128::MAX - 2, u128::MAX - 1)] {
+            let inv = mod_inverse(a, m).unwrap();
+            assert!(inv < m);
+            assert_eq!(mul_mod(a, inv, m), 1);
+        }
+        assert_eq!(mod_inverse(2, u128::MAX - 1), None);
+    }
+
+    #[test]
+    fn mul_mod_without_overflow() {
+        assert_eq!(mul_mod(u128::MAX - 1, u128::MAX - 1, u128::MAX), 1);
+        assert_eq!(mul_mod(1 << 100, 1 << 100, (1 << 127) + 1), mul_mod(1 << 73, 1 << 127, (1 << 127) + 1));
+    }
+
+    #[test]
+    fn crt_with_coprime_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(10, 7)]), Some((3, 7)));
+    }
+
+    #[test]
+    fn crt_with_non_coprime_moduli() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(0, 6), (0, 10), (0, 15)]), Some((0, 30)));
+        assert_eq!(crt(&[(3, 6), (3, 6)]), Some((3, 6)));
+    }
+
+    #[test]
+    fn crt_with_contradicting_congruences() {
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(0, 2), (1, 2)]), None);
+    }
+
+    #[test]
+    fn crt_with_zero_modulus() {
+        assert_eq!(crt(&[(1, 0)]), None);
+    }
+
+    #[test]
+    fn crt_with_big_moduli() {
+        let (p1, p2) = ((1u128 << 61) - 1, (1u128 << 66) - 5);
+        let (x, l) = crt(&[(5, p1), (7, p2)]).unwrap();
+        assert_eq!(l, p1 * p2);
+        assert_eq!(x % p1, 5);
+        assert_eq!(x % p2, 7);
+    }
+
+    #[test]
+    fn crt_lcm_overflow() {
+        assert_eq!(crt(&[(1, 1 << 100), (0, (1 << 100) + 1)]), None);
+    }
+}
@@ -0,0 +1,400 @@
+use std::any::type_name;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use nom::character::complete::{newline, space0, space1};
+use nom::combinator::all_consuming;
+use nom::error::{ContextError, ErrorKind, FromExternalError};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, terminated, tuple};
+use nom::Finish;
+
+/// nom's `IResult`, with an error type that remembers what the parser was looking for
+pub type IResult<I, O> = nom::IResult<I, O, NomError<I>>;
+
+/// What a parser expected to find where it failed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expected {
+    Tag(&'static str),
+    Char(char),
+    OneOf(&'static str),
+    Context(&'static str),
+    /// A number in range for the named integer type
+    Fits(&'static str),
+    Kind(ErrorKind),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Tag(tag) => write!(f, "{tag:?}"),
+            Expected::Char(ch) => write!(f, "{ch:?}"),
+            Expected::OneOf(chars) => write!(f, "one of {chars:?}"),
+            Expected::Context(context) => write!(f, "{context}"),
+            Expected::Fits(ty) => write!(f, "a number that fits in {ty}"),
+            Expected::Kind(kind) => f.write_str(match kind {
+                ErrorKind::Digit => "a digit",
+                ErrorKind::Alpha => "a letter",
+                ErrorKind::AlphaNumeric => "a letter or digit",
+                ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+                ErrorKind::Eof => "end of input",
+                ErrorKind::MapRes => "a valid value",
+                ErrorKind::Many1 | ErrorKind::SeparatedList => "at least one item",
+                ErrorKind::TakeWhile1 => "at least one matching character",
+                ErrorKind::Tag => "literal text",
+                kind => kind.description(),
+            }),
+        }
+    }
+}
+
+/// nom error type recording the remaining input where parsing failed and what was expected there
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NomError<I> {
+    pub input: I,
+    pub expected: Expected,
+}
+
+impl<I> nom::error::ParseError<I> for NomError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        NomError { input, expected: Expected::Kind(kind) }
+    }
+
+    // Keep the innermost error, it says the most about what was expected
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, ch: char) -> Self {
+        NomError { input, expected: Expected::Char(ch) }
+    }
+}
+
+impl<I> ContextError<I> for NomError<I> {
+    // A number out of range is more use than any context around it
+    fn add_context(input: I, context: &'static str, other: Self) -> Self {
+        match other.expected {
+            Expected::Fits(_) => other,
+            _ => NomError { input, expected: Expected::Context(context) },
+        }
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for NomError<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        NomError { input, expected: Expected::Kind(kind) }
+    }
+}
+
+/// `nom::bytes::complete::tag`, but failures say which tag was expected
+pub fn tag<'a>(tag: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input: &'a str| match input.strip_prefix(tag) {
+        Some(rest) => Ok((rest, &input[..tag.len()])),
+        None => Err(nom::Err::Error(NomError { input, expected: Expected::Tag(tag) })),
+    }
+}
+
+/// `nom::character::complete::one_of`, but failures say which characters were expected
+pub fn one_of<'a>(chars: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, char> {
+    move |input: &'a str| match input.chars().next() {
+        Some(ch) if chars.contains(ch) => Ok((&input[ch.len_utf8()..], ch)),
+        _ => Err(nom::Err::Error(NomError { input, expected: Expected::OneOf(chars) })),
+    }
+}
+
+/// The integer types `number` and `unsigned` can parse
+pub trait Integer: FromStr {
+    /// Whether a leading `-` is allowed
+    const SIGNED: bool;
+}
+
+macro_rules! integer {
+    ($signed:literal: $($t:ty),*) => {$(
+        impl Integer for $t {
+            const SIGNED: bool = $signed;
+        }
+    )*};
+}
+
+integer!(false: u8, u16, u32, u64, u128, usize);
+integer!(true: i8, i16, i32, i64, i128, isize);
+
+/// A decimal integer of type `T`, with a leading `-` if `T` is signed. A number too big for `T`
+/// fails outright rather than letting another branch try, so its error is the one reported.
+pub fn number<T: Integer>(input: &str) -> IResult<&str, T> {
+    integer(input, T::SIGNED)
+}
+
+/// Like `number`, but never with a `-`, even if `T` is signed. For counts, ids and lengths that
+/// are kept in signed types to do sums with.
+pub fn unsigned<T: Integer>(input: &str) -> IResult<&str, T> {
+    integer(input, false)
+}
+
+fn integer<T: Integer>(input: &str, signed: bool) -> IResult<&str, T> {
+    let sign = usize::from(signed && input.starts_with('-'));
+    let digits = input[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len() - sign);
+    if digits == 0 {
+        let expected = if signed { "a number" } else { "a number without a sign" };
+        return Err(nom::Err::Error(NomError { input, expected: Expected::Context(expected) }))
+    }
+    let (text, rest) = input.split_at(sign + digits);
+    match text.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => Err(nom::Err::Failure(NomError { input, expected: Expected::Fits(type_name::<T>()) })),
+    }
+}
+
+/// One or more numbers separated by spaces, like `7  15   30`
+pub fn numbers<T: Integer>(input: &str) -> IResult<&str, Vec<T>> {
+    space_separated(number)(input)
+}
+
+/// Like `numbers`, none of them with a `-`
+pub fn unsigned_numbers<T: Integer>(input: &str) -> IResult<&str, Vec<T>> {
+    space_separated(unsigned)(input)
+}
+
+/// One or more of `parser` separated by spaces
+pub fn space_separated<'a, O>(parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(space1, parser)
+}
+
+/// One or more of `parser` separated by commas, each optionally followed by spaces
+pub fn comma_separated<'a, O>(parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(tuple((tag(","), space0)), parser)
+}
+
+/// One or more lines, each holding one record for `parser`
+pub fn lines<'a, O>(parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    many1(terminated(parser, newline))
+}
+
+/// One or more blocks separated by blank lines. `parser` reads a block up to and including the
+/// newline at the end of its last line, as `lines` does.
+pub fn blocks<'a, O>(parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(newline, parser)
+}
+
+/// A field like `Time:  7  15   30`: `label`, a colon and any spaces, then `parser`
+pub fn field<'a, O>(label: &'static str, parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(label), tag(":"), space0)), parser)
+}
+
+/// A parse failure, located by line and column within the puzzle input
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: Expected,
+    pub line_text: String,
+}
+
+impl ParseError {
+    /// Locate a failure at `at` within `source`. `at` should be a slice of `source`; if it isn't,
+    /// the failure is reported at the end of `source`.
+    pub fn new(source: &str, at: &str, expected: Expected) -> ParseError {
+        let offset = (at.as_ptr() as usize).checked_sub(source.as_ptr() as usize)
+            .filter(|&offset| offset <= source.len())
+            .unwrap_or(source.len());
+        let line_start = source[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[offset..].find('\n').map(|i| offset + i).unwrap_or(source.len());
+        ParseError {
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            expected,
+            line_text: source[line_start..line_end].to_string(),
+        }
+    }
+
+    pub fn from_nom(source: &str, error: NomError<&str>) -> ParseError {
+        ParseError::new(source, error.input, error.expected)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "line {}, column {}: expected {}", self.line, self.column, self.expected)?;
+        writeln!(f, "    {}", self.line_text.escape_debug())?;
+        write!(f, "    {:>width$}", "^", width=self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Run `parser` over all of `input`, turning any failure into a located `ParseError`
+pub fn parse_all<'a, O>(input: &'a str, parser: impl FnMut(&'a str) -> IResult<&'a str, O>) -> Result<O, ParseError> {
+    all_consuming(parser)(input).finish()
+        .map(|(_, output)| output)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+fn grid_rows<'a>(input: &'a str, allowed: &impl Fn(char) -> bool, expected: &Expected) -> Result<Vec<Vec<u8>>, NomError<&'a str>> {
+    let mut grid: Vec<Vec<u8>> = Vec::new();
+    for line in input.lines() {
+        if let Some(bad) = line.find(|c: char| !allowed(c)) {
+            return Err(NomError { input: &line[bad..], expected: expected.clone() })
+        }
+        if grid.first().is_some_and(|first| first.len() != line.len()) {
+            return Err(NomError { input: line, expected: Expected::Context("a row as long as the first row") })
+        }
+        grid.push(line.as_bytes().to_vec());
+    }
+    if grid.is_empty() {
+        return Err(NomError { input, expected: Expected::Context("at least one row") })
+    }
+    Ok(grid)
+}
+
+/// Split `input` into a rectangular grid of bytes, one row per line, checking that every cell is
+/// one of `allowed`
+pub fn parse_grid(input: &str, allowed: &'static str) -> Result<Vec<Vec<u8>>, ParseError> {
+    parse_grid_with(input, |c| allowed.contains(c), Expected::OneOf(allowed))
+}
+
+/// Like `parse_grid`, with a predicate for the characters allowed in a cell and what to report
+/// when a cell isn't one of them
+pub fn parse_grid_with(input: &str, allowed: impl Fn(char) -> bool, expected: Expected) -> Result<Vec<Vec<u8>>, ParseError> {
+    grid_rows(input, &allowed, &expected).map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// Like `parse_grid`, for several grids separated by blank lines
+pub fn parse_grids(input: &str, allowed: &'static str) -> Result<Vec<Vec<Vec<u8>>>, ParseError> {
+    let allowed_char = |c| allowed.contains(c);
+    input.split("\n\n")
+        .map(|block| grid_rows(block, &allowed_char, &Expected::OneOf(allowed)).map_err(|e| ParseError::from_nom(input, e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::branch::alt;
+    use nom::character::complete::{alpha1, digit1};
+    use nom::combinator::{map, value};
+    use nom::error::context;
+
+    use super::*;
+
+    fn error<T>(result: IResult<&str, T>) -> nom::Err<NomError<&str>> {
+        match result {
+            Ok(_) => panic!("expected the parser to fail"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn number_parses_signs_for_signed_types() {
+        assert_eq!(number::<i64>("-12 rest"), Ok((" rest", -12)));
+        assert_eq!(number::<i64>("12"), Ok(("", 12)));
+        assert_eq!(number::<u32>("007,"), Ok((",", 7)));
+        assert_eq!(error(number::<u32>("-5")), nom::Err::Error(NomError { input: "-5", expected: Expected::Context("a number without a sign") }));
+    }
+
+    #[test]
+    fn bare_minus_is_not_a_number() {
+        assert_eq!(error(number::<i32>("-")), nom::Err::Error(NomError { input: "-", expected: Expected::Context("a number") }));
+        assert_eq!(error(number::<i32>("- 3")), nom::Err::Error(NomError { input: "- 3", expected: Expected::Context("a number") }));
+        assert!(number::<i32>("").is_err());
+    }
+
+    #[test]
+    fn unsigned_rejects_a_sign_even_for_signed_types() {
+        assert_eq!(unsigned::<i64>("3"), Ok(("", 3)));
+        assert_eq!(error(unsigned::<i64>("-3")), nom::Err::Error(NomError { input: "-3", expected: Expected::Context("a number without a sign") }));
+        assert!(unsigned::<i64>("+3").is_err());
+        assert_eq!(unsigned_numbers::<i64>("1 -2"), Ok((" -2", vec![1])));
+    }
+
+    #[test]
+    fn overflow_is_a_failure() {
+        assert_eq!(number::<u8>("255"), Ok(("", 255)));
+        assert_eq!(error(number::<u8>("256")), nom::Err::Failure(NomError { input: "256", expected: Expected::Fits("u8") }));
+        assert_eq!(error(number::<i8>("-129")), nom::Err::Failure(NomError { input: "-129", expected: Expected::Fits("i8") }));
+
+        // So alt doesn't go on to try the other branches, which would hide the real problem
+        let mut number_or_digits = alt((map(number::<u8>, Some), value(None, digit1)));
+        assert_eq!(number_or_digits("12"), Ok(("", Some(12))));
+        assert!(matches!(number_or_digits("300"), Err(nom::Err::Failure(NomError { expected: Expected::Fits("u8"), .. }))));
+
+        // And context doesn't replace it
+        let mut with_context = context("a small number", number::<u8>);
+        assert!(matches!(with_context("300"), Err(nom::Err::Failure(NomError { expected: Expected::Fits("u8"), .. }))));
+        assert!(matches!(with_context("x"), Err(nom::Err::Error(NomError { expected: Expected::Context("a small number"), .. }))));
+    }
+
+    #[test]
+    fn separated_lists() {
+        assert_eq!(numbers::<i32>("7  15   -30\n"), Ok(("\n", vec![7, 15, -30])));
+        assert_eq!(unsigned_numbers::<u32>("7 15 30"), Ok(("", vec![7, 15, 30])));
+        assert_eq!(comma_separated(alpha1)("a,b, c,d"), Ok(("", vec!["a", "b", "c", "d"])));
+        // A trailing separator is left for whatever comes next
+        assert_eq!(comma_separated(alpha1)("a,b,"), Ok((",", vec!["a", "b"])));
+        assert!(numbers::<i32>("").is_err());
+    }
+
+    #[test]
+    fn lines_need_a_newline_after_every_record() {
+        assert_eq!(lines(numbers::<i32>)("1 2\n3\n"), Ok(("", vec![vec![1, 2], vec![3]])));
+        assert_eq!(lines(numbers::<i32>)("1 2\n3"), Ok(("3", vec![vec![1, 2]])));
+        assert!(lines(numbers::<i32>)("").is_err());
+    }
+
+    #[test]
+    fn blocks_are_separated_by_blank_lines() {
+        assert_eq!(blocks(lines(alpha1))("a\nb\n\nc\n"), Ok(("", vec![vec!["a", "b"], vec!["c"]])));
+        // The blank line after the last block isn't part of it
+        assert_eq!(blocks(lines(alpha1))("a\n\n"), Ok(("\n", vec![vec!["a"]])));
+    }
+
+    #[test]
+    fn field_with_label() {
+        assert_eq!(field("Time", numbers::<u32>)("Time:  7  15   30"), Ok(("", vec![7, 15, 30])));
+        assert_eq!(field("Time", numbers::<u32>)("Time:7"), Ok(("", vec![7])));
+        assert_eq!(error(field("Time", numbers::<u32>)("Distance: 9")), nom::Err::Error(NomError { input: "Distance: 9", expected: Expected::Tag("Time") }));
+    }
+
+    #[test]
+    fn tag_and_one_of_say_what_they_expected() {
+        assert_eq!(error(tag("->")(" ->")), nom::Err::Error(NomError { input: " ->", expected: Expected::Tag("->") }));
+        assert_eq!(one_of("LR")("R1"), Ok(("1", 'R')));
+        assert_eq!(one_of("éR")("é1"), Ok(("1", 'é')));
+        assert_eq!(error(one_of("LR")("X")), nom::Err::Error(NomError { input: "X", expected: Expected::OneOf("LR") }));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let source = "first line\nsecond x\n";
+        let error = ParseError::new(source, &source[18..], Expected::Char('y'));
+        assert_eq!((error.line, error.column, error.line_text.as_str()), (2, 8, "second x"));
+        assert_eq!(error.to_string(), "line 2, column 8: expected 'y'\n    second x\n           ^");
+    }
+
+    #[test]
+    fn parse_error_column_counts_characters() {
+        let source = "héllo\nwörld x\n";
+        let at = &source[source.find('x').unwrap()..];
+        let error = ParseError::new(source, at, Expected::Char('y'));
+        assert_eq!((error.line, error.column, error.line_text.as_str()), (2, 7, "wörld x"));
+    }
+
+    #[test]
+    fn parse_error_outside_source_is_at_the_end() {
+        let source = "ab\ncd\n";
+        let elsewhere = String::from("cd\n");
+        let error = ParseError::new(source, &elsewhere, Expected::Char('y'));
+        assert_eq!((error.line, error.column, error.line_text.as_str()), (3, 1, ""));
+
+        let error = ParseError::new(source, &source[source.len()..], Expected::Char('y'));
+        assert_eq!((error.line, error.column), (3, 1));
+    }
+
+    #[test]
+    fn parse_all_needs_all_the_input() {
+        assert_eq!(parse_all("1 2\n", lines(numbers::<i32>)), Ok(vec![vec![1, 2]]));
+        let error = parse_all("1 2\n3 x\n", lines(numbers::<i32>)).unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (2, 1, Expected::Kind(ErrorKind::Eof)));
+        let error = parse_all("1 99999999999\n", lines(numbers::<i32>)).unwrap_err();
+        assert_eq!((error.line, error.column, error.expected), (1, 3, Expected::Fits("i32")));
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, write};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use color_eyre::Report;
+use owo_colors::{AnsiColors, OwoColorize};
+use tracing::{info, warn};
+
+use crate::grid::Grid;
+
+/// The colours a cell can be drawn in
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    White,
+    Blue,
+    Yellow,
+    BrightBlack,
+    BrightYellow,
+}
+
+impl Color {
+    fn ansi(self) -> AnsiColors {
+        match self {
+            Color::Black => AnsiColors::Black,
+            Color::White => AnsiColors::White,
+            Color::Blue => AnsiColors::Blue,
+            Color::Yellow => AnsiColors::Yellow,
+            Color::BrightBlack => AnsiColors::BrightBlack,
+            Color::BrightYellow => AnsiColors::BrightYellow,
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Black => [0, 0, 0],
+            Color::White => [229, 229, 229],
+            Color::Blue => [36, 114, 200],
+            Color::Yellow => [188, 160, 0],
+            Color::BrightBlack => [102, 102, 102],
+            Color::BrightYellow => [245, 245, 67],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Style {
+        Style { fg: Some(color), bg: None }
+    }
+
+    pub fn on(self, color: Color) -> Style {
+        Style { bg: Some(color), ..self }
+    }
+}
+
+/// One cell of a grid: its text, usually a single character, and how to draw it
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Cell {
+    pub fn new(text: impl ToString, style: Style) -> Cell {
+        Cell { text: text.to_string(), style }
+    }
+
+    pub fn plain(ch: char) -> Cell {
+        Cell::new(ch, Style::default())
+    }
+}
+
+/// A grid of styled cells, ready to hand to a `Renderer`
+#[derive(Debug, Clone, Default)]
+pub struct Canvas {
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    /// A canvas showing each byte of `grid` as a character, styled by `style`
+    pub fn from_bytes(grid: &Grid<u8>, style: impl Fn(u8) -> Style) -> Canvas {
+        Canvas {
+            rows: grid.rows().map(|row| row.iter().map(|&b| Cell::new(b as char, style(b))).collect()).collect(),
+        }
+    }
+}
+
+/// Turns a titled canvas into the bytes of some output format
+pub trait Renderer {
+    fn render(&self, title: &str, canvas: &Canvas) -> Vec<u8>;
+
+    /// File extension for the output, or `None` if it's text meant for the terminal
+    fn extension(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Text coloured with ANSI escape codes
+pub struct Ansi;
+
+impl Renderer for Ansi {
+    fn render(&self, title: &str, canvas: &Canvas) -> Vec<u8> {
+        let mut out = format!("{title}\n");
+        for row in &canvas.rows {
+            for cell in row {
+                let _ = match (cell.style.fg, cell.style.bg) {
+                    (Some(fg), Some(bg)) => write!(out, "{}", cell.text.color(fg.ansi()).on_color(bg.ansi())),
+                    (Some(fg), None) => write!(out, "{}", cell.text.color(fg.ansi())),
+                    (None, Some(bg)) => write!(out, "{}", cell.text.on_color(bg.ansi())),
+                    (None, None) => write!(out, "{}", cell.text),
+                };
+            }
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+}
+
+/// Just the text
+pub struct Plain;
+
+impl Renderer for Plain {
+    fn render(&self, title: &str, canvas: &Canvas) -> Vec<u8> {
+        let mut out = format!("{title}\n");
+        for row in &canvas.rows {
+            for cell in row {
+                out.push_str(&cell.text);
+            }
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+}
+
+/// A standalone HTML page with the grid in a `<pre>`
+pub struct Html;
+
+impl Renderer for Html {
+    fn render(&self, title: &str, canvas: &Canvas) -> Vec<u8> {
+        let css = |color: Color| {
+            let [r, g, b] = color.rgb();
+            format!("#{r:02x}{g:02x}{b:02x}")
+        };
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body style=\"background: #000; color: #e5e5e5\">\n<h1>{0}</h1>\n<pre>\n",
+            escape_html(title)
+        );
+        for row in &canvas.rows {
+            for cell in row {
+                let text = escape_html(&cell.text);
+                let mut style = String::new();
+                if let Some(fg) = cell.style.fg {
+                    style.push_str(&format!("color: {};", css(fg)));
+                }
+                if let Some(bg) = cell.style.bg {
+                    style.push_str(&format!("background: {};", css(bg)));
+                }
+                if style.is_empty() {
+                    out.push_str(&text);
+                } else {
+                    let _ = write!(out, "<span style=\"{style}\">{text}</span>");
+                }
+            }
+            out.push('\n');
+        }
+        out.push_str("</pre>\n</body></html>\n");
+        out.into_bytes()
+    }
+
+    fn extension(&self) -> Option<&'static str> {
+        Some("html")
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// A binary PPM image with a square block of pixels per cell. A cell takes its background colour,
+/// or its foreground colour if it has no background. Unstyled cells are white unless they're
+/// blank or `.`, which are black.
+pub struct Ppm {
+    pub scale: usize,
+}
+
+impl Renderer for Ppm {
+    fn render(&self, _title: &str, canvas: &Canvas) -> Vec<u8> {
+        let height = canvas.rows.len();
+        let width = canvas.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut out = format!("P6\n{} {}\n255\n", width * self.scale, height * self.scale).into_bytes();
+        for row in &canvas.rows {
+            let mut line = Vec::with_capacity(width * self.scale * 3);
+            for col in 0..width {
+                let color = match row.get(col) {
+                    None => Color::Black,
+                    Some(cell) => cell.style.bg.or(cell.style.fg).unwrap_or(
+                        if cell.text.trim().is_empty() || cell.text == "." { Color::Black } else { Color::White }
+                    ),
+                };
+                for _ in 0..self.scale {
+                    line.extend_from_slice(&color.rgb());
+                }
+            }
+            for _ in 0..self.scale {
+                out.extend_from_slice(&line);
+            }
+        }
+        out
+    }
+
+    fn extension(&self) -> Option<&'static str> {
+        Some("ppm")
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Ansi,
+    Plain,
+    Html,
+    Ppm,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Format::Ansi),
+            "plain" => Ok(Format::Plain),
+            "html" => Ok(Format::Html),
+            "ppm" => Ok(Format::Ppm),
+            s => Err(format!("unknown render format {s:?}, expected ansi, plain, html or ppm")),
+        }
+    }
+}
+
+struct Settings {
+    renderer: Box<dyn Renderer + Send + Sync>,
+    dir: PathBuf,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+static RENDERED: AtomicUsize = AtomicUsize::new(0);
+
+/// Turn rendering on. Text formats go to stderr and ANSI falls back to plain text when `NO_COLOR`
+/// is set; image and HTML formats are written to numbered files in `dir`.
+pub fn enable(format: Format, dir: PathBuf) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let renderer: Box<dyn Renderer + Send + Sync> = match format {
+        Format::Ansi if no_color => Box::new(Plain),
+        Format::Ansi => Box::new(Ansi),
+        Format::Plain => Box::new(Plain),
+        Format::Html => Box::new(Html),
+        Format::Ppm => Box::new(Ppm { scale: 4 }),
+    };
+    if SETTINGS.set(Settings { renderer, dir }).is_err() {
+        warn!("rendering was already enabled");
+    }
+}
+
+pub fn enabled() -> bool {
+    SETTINGS.get().is_some()
+}
+
+/// Render the canvas built by `canvas` if rendering is on. The canvas is only built when it's
+/// going to be shown, so this costs nothing otherwise.
+pub fn show(title: &str, canvas: impl FnOnce() -> Canvas) {
+    let Some(settings) = SETTINGS.get() else { return };
+    if let Err(e) = output(settings, title, &canvas()) {
+        warn!("rendering {title:?}: {e}");
+    }
+}
+
+fn output(settings: &Settings, title: &str, canvas: &Canvas) -> Result<(), Report> {
+    let rendered = settings.renderer.render(title, canvas);
+    match settings.renderer.extension() {
+        None => std::io::stderr().lock().write_all(&rendered)?,
+        Some(extension) => {
+            let seq = RENDERED.fetch_add(1, Ordering::Relaxed) + 1;
+            let slug: String = title.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+                .collect();
+            create_dir_all(&settings.dir)?;
+            let path = settings.dir.join(format!("{seq:04}-{slug}.{extension}"));
+            write(&path, rendered)?;
+            info!("rendered {}", path.display());
+        }
+    }
+    Ok(())
+}
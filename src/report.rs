@@ -0,0 +1,88 @@
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::runner::{DayRun, RunStatus};
+
+/// How results are printed on stdout
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            s => Err(format!("unknown output format {s:?}, expected text or json")),
+        }
+    }
+}
+
+/// One JSON document describing `runs`. Answers are strings, as numeric answers can be too big
+/// for a JSON reader to hold exactly.
+///
+/// ```json
+/// {"runs": [{"day": 6, "title": "Wait For It", "status": "solved", "error": null,
+///            "input": "inputs/day06.txt", "input_hash": "fnv1a64:...",
+///            "answers": {"1": "288", "2": "71503"},
+///            "timings_ns": {"parse": 1200, "part1": 300, "part2": 250}, "warnings": []}]}
+/// ```
+pub fn json(runs: &[DayRun]) -> String {
+    let runs: Vec<String> = runs.iter().map(run_json).collect();
+    format!("{{\"runs\": [{}]}}", runs.join(", "))
+}
+
+fn run_json(run: &DayRun) -> String {
+    let title = crate::solver::find(run.day).map(|s| string(s.title())).unwrap_or_else(|| "null".to_string());
+    let input = run.input.as_ref().map(|p| string(&p.display().to_string())).unwrap_or_else(|| "null".to_string());
+    let input_hash = run.input_hash.map(|h| string(&format!("fnv1a64:{h:016x}"))).unwrap_or_else(|| "null".to_string());
+    let (status, error, answers, timings) = match &run.status {
+        RunStatus::Solved { answers, timings } => {
+            let answers: Vec<String> = answers.iter().map(|(p, a)| format!("\"{p}\": {}", string(&a.to_string()))).collect();
+            let mut phases = vec![format!("\"parse\": {}", nanos(timings.parse))];
+            for (name, time) in [("part1", timings.part1), ("part2", timings.part2)] {
+                if let Some(time) = time {
+                    phases.push(format!("\"{name}\": {}", nanos(time)));
+                }
+            }
+            ("solved", "null".to_string(), format!("{{{}}}", answers.join(", ")), format!("{{{}}}", phases.join(", ")))
+        }
+        RunStatus::Skipped(reason) => ("skipped", string(reason), "{}".to_string(), "null".to_string()),
+        RunStatus::Failed(error) => ("failed", string(error), "{}".to_string(), "null".to_string()),
+    };
+    let warnings: Vec<String> = run.warnings.iter().map(|w| string(w)).collect();
+
+    format!(
+        "{{\"day\": {}, \"title\": {title}, \"status\": \"{status}\", \"error\": {error}, \"input\": {input}, \"input_hash\": {input_hash}, \"answers\": {answers}, \"timings_ns\": {timings}, \"warnings\": [{}]}}",
+        run.day, warnings.join(", ")
+    )
+}
+
+fn nanos(duration: Duration) -> u128 {
+    duration.as_nanos()
+}
+
+/// A JSON string literal
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", ch as u32); }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,114 @@
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::{filter_fn, FilterFn, Filtered};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use crate::answer::Answers;
+
+/// An answer a solver logged with `info!(day=.., part=.., answer=..)`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LoggedAnswer {
+    /// The module that logged it, e.g. `advent23::day10`
+    pub target: String,
+    pub day: Option<String>,
+    pub part: Option<String>,
+    pub answer: String,
+}
+
+impl LoggedAnswer {
+    /// The day of the module that logged the answer, if it was logged by a day module
+    pub fn module_day(&self) -> Option<u32> {
+        self.target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::day"))?.parse().ok()
+    }
+}
+
+static LOGGED: Mutex<Vec<LoggedAnswer>> = Mutex::new(Vec::new());
+
+/// Take every answer logged since the last call
+pub fn take() -> Vec<LoggedAnswer> {
+    std::mem::take(&mut *LOGGED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Take the answers logged while solving `day` and describe anything odd about them: a `day` field
+/// that isn't the module's day, or a logged answer that isn't the one the solver returned
+pub fn check(day: u32, answers: &Answers) -> Vec<String> {
+    let mut problems = Vec::new();
+    for logged in take() {
+        let Some(module_day) = logged.module_day() else { continue };
+        if module_day != day {
+            continue
+        }
+        let logged_day = logged.day.as_deref().unwrap_or("nothing");
+        let logged_part = logged.part.as_deref().unwrap_or("nothing");
+        if logged_day != day.to_string() {
+            problems.push(format!("{} logs day={logged_day} for part={logged_part} but solves day {day}", logged.target));
+        }
+        let part = logged_part.parse::<u32>().ok();
+        if let Some((part, returned)) = part.and_then(|p| answers.iter().find(|(ap, _)| *ap == p)) {
+            if returned.to_string() != logged.answer {
+                problems.push(format!("{} logs answer={} for part {part} but returns {returned}", logged.target, logged.answer));
+            }
+        }
+    }
+    problems
+}
+
+type AnswerFilter = FilterFn<fn(&Metadata<'_>) -> bool>;
+
+/// A layer that collects every event with an `answer` field, for `take` and `check`
+pub struct ResultsLayer;
+
+impl ResultsLayer {
+    /// The layer, filtered down to the events it collects so that it never enables anything else
+    pub fn filtered<S: Subscriber>() -> Filtered<ResultsLayer, AnswerFilter, S> {
+        let is_answer: fn(&Metadata<'_>) -> bool = |meta| meta.is_event() && *meta.level() <= Level::INFO && meta.fields().field("answer").is_some();
+        ResultsLayer.with_filter(filter_fn(is_answer))
+    }
+}
+
+impl<S: Subscriber> Layer<S> for ResultsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = AnswerVisitor::default();
+        event.record(&mut visitor);
+        if let Some(answer) = visitor.answer {
+            LOGGED.lock().unwrap_or_else(|e| e.into_inner()).push(LoggedAnswer {
+                target: event.metadata().target().to_string(),
+                day: visitor.day,
+                part: visitor.part,
+                answer,
+            });
+        }
+    }
+}
+
+#[derive(Default)]
+struct AnswerVisitor {
+    day: Option<String>,
+    part: Option<String>,
+    answer: Option<String>,
+}
+
+impl AnswerVisitor {
+    fn set(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "day" => self.day = Some(value),
+            "part" => self.part = Some(value),
+            "answer" => self.answer = Some(value),
+            _ => {}
+        }
+    }
+}
+
+impl Visit for AnswerVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.set(field, format!("{value:?}"));
+    }
+}
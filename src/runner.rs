@@ -0,0 +1,141 @@
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use fnv::FnvHasher;
+use tracing::warn;
+
+use crate::answer::Answers;
+use crate::solver::{Solver, Timings};
+
+/// A set of days to run, written like `1-5,8,12`
+#[derive(Debug, Clone)]
+pub struct DaySelection(Vec<u32>);
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = Vec::new();
+        for item in s.split(',').map(str::trim) {
+            let parse_day = |d: &str| d.trim().parse::<u32>().map_err(|_| format!("invalid day {d:?} in {s:?}"));
+            match item.split_once('-') {
+                Some((first, last)) => {
+                    let (first, last) = (parse_day(first)?, parse_day(last)?);
+                    if first > last {
+                        return Err(format!("empty day range {item:?}"))
+                    }
+                    days.extend(first..=last);
+                }
+                None => days.push(parse_day(item)?),
+            }
+        }
+        days.sort();
+        days.dedup();
+        Ok(DaySelection(days))
+    }
+}
+
+impl DaySelection {
+    pub fn all(solvers: &[&dyn Solver]) -> DaySelection {
+        DaySelection(solvers.iter().map(|s| s.day()).collect())
+    }
+
+    pub fn days(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+/// Where the input for `day` lives by convention, e.g. `inputs/day05.txt`
+pub fn input_path(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day{day:02}.txt"))
+}
+
+/// How running one day went
+#[derive(Debug)]
+pub enum RunStatus {
+    Solved { answers: Answers, timings: Timings },
+    Skipped(String),
+    Failed(String),
+}
+
+/// One day's run: what it was run on, how it went and anything odd noticed along the way
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u32,
+    pub part: Option<u32>,
+    /// The input file, or `None` for input given on the command line
+    pub input: Option<PathBuf>,
+    /// FNV-1a hash of the input text as the solver saw it
+    pub input_hash: Option<u64>,
+    pub status: RunStatus,
+    pub warnings: Vec<String>,
+}
+
+/// Solve `input` with `solver`, collecting warnings about the answers it logs
+pub fn solve_input(solver: &dyn Solver, input_path: Option<&Path>, input: String, part: Option<u32>) -> DayRun {
+    let day = solver.day();
+    let mut hasher = FnvHasher::default();
+    hasher.write(input.as_bytes());
+    let input_hash = Some(hasher.finish());
+
+    let (status, warnings) = match solver.solve_timed(input, part) {
+        Ok((answers, timings)) => {
+            let warnings = crate::results::check(day, &answers);
+            for problem in &warnings {
+                warn!("{problem}");
+            }
+            (RunStatus::Solved { answers, timings }, warnings)
+        }
+        Err(e) => (RunStatus::Failed(e.to_string()), Vec::new()),
+    };
+    DayRun { day, part, input: input_path.map(Path::to_path_buf), input_hash, status, warnings }
+}
+
+/// Run `solver` against its conventional input file in `dir`, skipping it if there's no input
+pub fn run_day(solver: &dyn Solver, dir: &Path, part: Option<u32>, raw: bool) -> DayRun {
+    let path = input_path(dir, solver.day());
+    if !path.exists() {
+        let reason = format!("{} not found", path.display());
+        return DayRun { day: solver.day(), part, input: Some(path), input_hash: None, status: RunStatus::Skipped(reason), warnings: Vec::new() }
+    }
+
+    match crate::input::read_input(&path, raw) {
+        Ok(input) => solve_input(solver, Some(&path), input, part),
+        Err(e) => DayRun { day: solver.day(), part, input: Some(path), input_hash: None, status: RunStatus::Failed(e.to_string()), warnings: Vec::new() },
+    }
+}
+
+/// Run every selected day against its conventional input file in `dir`
+pub fn run_all(selection: &DaySelection, dir: &Path, part: Option<u32>, raw: bool) -> Vec<DayRun> {
+    selection.days().iter().map(|&day| match crate::solver::find(day) {
+        Some(solver) => run_day(solver, dir, part, raw),
+        None => DayRun { day, part, input: None, input_hash: None, status: RunStatus::Skipped("no such puzzle".to_string()), warnings: Vec::new() },
+    }).collect()
+}
+
+/// Print a table with a row per day and part
+pub fn print_summary(runs: &[DayRun]) {
+    println!("{:>3}  {:>4}  {:<20}  Status", "Day", "Part", "Answer");
+    for run in runs {
+        let parts = match run.part {
+            Some(p) => vec![p],
+            None => (1..=crate::solver::find(run.day).map_or(2, |s| s.parts())).collect(),
+        };
+        let row = |part: &str, answer: &str, status: &str| println!("{:>3}  {:>4}  {:<20}  {}", run.day, part, answer, status);
+        match &run.status {
+            RunStatus::Solved { answers, .. } => for p in parts {
+                match answers.iter().find(|(ap, _)| *ap == p) {
+                    Some((_, answer)) => row(&p.to_string(), &answer.to_string(), "ok"),
+                    None => row(&p.to_string(), "", "no answer"),
+                }
+            },
+            RunStatus::Skipped(reason) => row(&part_label(run.part), "", &format!("skipped: {reason}")),
+            RunStatus::Failed(error) => row(&part_label(run.part), "", &format!("failed: {error}")),
+        }
+    }
+}
+
+fn part_label(part: Option<u32>) -> String {
+    part.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,92 @@
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+
+use color_eyre::eyre::eyre;
+use color_eyre::Report;
+use regex::Regex;
+use tracing::info;
+
+/// The module a new day starts from. It's `src/day.rs`, which isn't part of the build itself.
+const TEMPLATE: &str = include_str!("day.rs");
+
+/// Create `src/dayN.rs` from the template along with empty `examples/dayNN.txt` and input files,
+/// and register the new day in `src/lib.rs` and the solver list so that it's ready to run.
+/// `root` is the crate directory.
+pub fn new_day(root: &Path, inputs: &Path, day: u32, title: &str) -> Result<(), Report> {
+    if day == 0 {
+        return Err(eyre!("Days start at 1"))
+    }
+    let lib_path = root.join("src/lib.rs");
+    let solver_path = root.join("src/solver.rs");
+    if !lib_path.exists() || !solver_path.exists() {
+        return Err(eyre!("{} doesn't look like the puzzle crate, run this from its top directory", root.display()))
+    }
+    let module_path = root.join(format!("src/day{day}.rs"));
+    if module_path.exists() {
+        return Err(eyre!("{} already exists", module_path.display()))
+    }
+
+    let lib = register(&read_to_string(&lib_path)?, day, r"(?m)^pub mod day(\d+);$", &format!("pub mod day{day};"))?;
+    let solver = read_to_string(&solver_path)?;
+    let solver = register(&solver, day, r"(?m)^    &day(\d+)::Day\d+,$", &format!("    &day{day}::Day{day},"))?;
+    let solver = register_use(&solver, day)?;
+
+    let module = TEMPLATE
+        .replace("day00.txt", &format!("day{day:02}.txt"))
+        .replace("Day0", &format!("Day{day}"))
+        .replace("DAY: u32 = 0", &format!("DAY: u32 = {day}"))
+        .replace("TITLE: &'static str = \"\"", &format!("TITLE: &'static str = {title:?}"))
+        .replace("day=0", &format!("day={day}"));
+
+    create_dir_all(root.join("examples"))?;
+    create_dir_all(inputs)?;
+    for path in [root.join(format!("examples/day{day:02}.txt")), crate::runner::input_path(inputs, day)] {
+        if !path.exists() {
+            write(&path, "")?;
+            info!("created {}", path.display());
+        }
+    }
+    write(&module_path, module)?;
+    info!("created {}", module_path.display());
+    write(&lib_path, lib)?;
+    write(&solver_path, solver)?;
+    info!("registered day {day} in {} and {}", lib_path.display(), solver_path.display());
+
+    Ok(())
+}
+
+/// Insert `line` into `source` among the lines matching `pattern`, keeping them in day order.
+/// The pattern's first group is the day number.
+fn register(source: &str, day: u32, pattern: &str, line: &str) -> Result<String, Report> {
+    let re = Regex::new(pattern)?;
+    let mut insert_at = None;
+    for caps in re.captures_iter(source) {
+        let whole = caps.get(0).unwrap();
+        let existing: u32 = caps[1].parse()?;
+        if existing == day {
+            return Err(eyre!("day {day} is already registered"))
+        }
+        if existing > day {
+            insert_at = Some(whole.start());
+            break
+        }
+        insert_at = Some(whole.end() + 1);
+    }
+    let Some(at) = insert_at else {
+        return Err(eyre!("no existing days to register day {day} alongside"))
+    };
+    let at = at.min(source.len());
+    Ok(format!("{}{line}\n{}", &source[..at], &source[at..]))
+}
+
+/// Add `dayN` to the `use crate::{day1, ...}` line that brings the day modules into the solver list
+fn register_use(source: &str, day: u32) -> Result<String, Report> {
+    let re = Regex::new(r"(?m)^use crate::\{(day\d+(?:, day\d+)*)\};$")?;
+    let caps = re.captures(source).ok_or_else(|| eyre!("can't find the day modules' use line"))?;
+    let mut days: Vec<u32> = caps[1].split(", ").map(|m| m.trim_start_matches("day").parse()).collect::<Result<_, _>>()?;
+    days.push(day);
+    days.sort();
+    let modules: Vec<String> = days.iter().map(|d| format!("day{d}")).collect();
+    let range = caps.get(0).unwrap().range();
+    Ok(format!("{}use crate::{{{}}};{}", &source[..range.start], modules.join(", "), &source[range.end..]))
+}
@@ -0,0 +1,50 @@
+use tracing::warn;
+
+use crate::solver::Solver;
+
+/// Run every solver against the worked examples from its puzzle text, normalized just like input
+/// files are, and print a line per day, part and example. Examples without expected answers yet,
+/// like a new day's, get a line saying so. Returns the number of examples that didn't give the
+/// expected answer.
+pub fn selftest(solvers: &[&dyn Solver], part: Option<u32>) -> usize {
+    let mut failures = 0;
+
+    for solver in solvers {
+        if solver.examples().is_empty() {
+            println!("{:>3}  {:>4}  no examples", solver.day(), "-");
+        }
+        for (idx, example) in solver.examples().iter().enumerate() {
+            if (1..=solver.parts()).all(|p| example.expected(p).is_none()) {
+                println!("{:>3}  {:>4}  example {}  no expected answer", solver.day(), "-", idx + 1);
+                continue
+            }
+            for p in 1..=solver.parts() {
+                if part.is_some_and(|want| want != p) {
+                    continue
+                }
+                let Some(want) = example.expected(p) else { continue };
+
+                let result = solver.solve(crate::input::normalize(example.input), Some(p));
+                if let Ok(answers) = &result {
+                    for problem in crate::results::check(solver.day(), answers) {
+                        warn!("{problem}");
+                    }
+                }
+                let status = match result {
+                    Ok(answers) => match answers.iter().find(|(ap, _)| *ap == p) {
+                        Some((_, got)) if got.to_string() == want => "pass".to_string(),
+                        Some((_, got)) => format!("FAIL: expected {want}, got {got}"),
+                        None => format!("FAIL: expected {want}, got no answer"),
+                    },
+                    Err(e) => format!("FAIL: {e}"),
+                };
+                if status != "pass" {
+                    failures += 1;
+                }
+                println!("{:>3}  {:>4}  example {}  {status}", solver.day(), p, idx + 1);
+            }
+        }
+    }
+
+    failures
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::Report;
+use tracing::info_span;
+
+use crate::answer::{Answer, Answers};
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15};
+
+pub trait Solver: Sync {
+    fn day(&self) -> u32;
+
+    fn title(&self) -> &'static str;
+
+    fn parts(&self) -> u32 {
+        2
+    }
+
+    /// Solve the puzzle for `input`, skipping any part other than `part` if one is given
+    fn solve(&self, input: String, part: Option<u32>) -> Result<Answers, Report> {
+        self.solve_timed(input, part).map(|(answers, _)| answers)
+    }
+
+    /// Like `solve`, but also report how long each phase took
+    fn solve_timed(&self, input: String, part: Option<u32>) -> Result<(Answers, Timings), Report>;
+
+    /// The worked examples from the puzzle text
+    fn examples(&self) -> &'static [Example];
+}
+
+/// A worked example from the puzzle text, with the answers the text gives for it. Parts without
+/// an answer aren't checked, since some examples only apply to one part.
+#[derive(Debug)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn expected(&self, part: u32) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+}
+
+/// How long each phase of a solver run took; parts that weren't run have no timing.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+/// The result of solving one part: `None` if the input doesn't support that part.
+pub type PartResult = Result<Option<Answer>, Report>;
+
+/// A day's puzzle, split into a parse phase and one phase per part so that each can be run on
+/// its own. Every `Puzzle` is a `Solver`.
+pub trait Puzzle: Sync {
+    const DAY: u32;
+    const TITLE: &'static str;
+    const EXAMPLES: &'static [Example];
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Report>;
+
+    fn part1(input: &Self::Input) -> PartResult;
+
+    fn part2(input: &Self::Input) -> PartResult;
+}
+
+impl<P: Puzzle> Solver for P {
+    fn day(&self) -> u32 {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        P::EXAMPLES
+    }
+
+    fn solve_timed(&self, input: String, part: Option<u32>) -> Result<(Answers, Timings), Report> {
+        let mut answers = Answers::default();
+        let mut timings = Timings::default();
+
+        let start = Instant::now();
+        let parsed = info_span!("parse", day=P::DAY).in_scope(|| P::parse(&input))?;
+        timings.parse = start.elapsed();
+
+        if part.is_none_or(|p| p == 1) {
+            let start = Instant::now();
+            if let Some(answer) = info_span!("part1", day=P::DAY).in_scope(|| P::part1(&parsed))? {
+                answers.set(1, answer);
+            }
+            timings.part1 = Some(start.elapsed());
+        }
+        if part.is_none_or(|p| p == 2) {
+            let start = Instant::now();
+            if let Some(answer) = info_span!("part2", day=P::DAY).in_scope(|| P::part2(&parsed))? {
+                answers.set(2, answer);
+            }
+            timings.part2 = Some(start.elapsed());
+        }
+        Ok((answers, timings))
+    }
+}
+
+// Every day module registers its solver here, in day order.
+static SOLVERS: &[&dyn Solver] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+];
+
+pub fn all() -> &'static [&'static dyn Solver] {
+    SOLVERS
+}
+
+pub fn find(day: u32) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().copied().find(|s| s.day() == day)
+}
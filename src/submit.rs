@@ -0,0 +1,341 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::str::FromStr;
+
+use color_eyre::eyre::eyre;
+use color_eyre::Report;
+use tracing::info;
+
+use crate::fetch::{Fetcher, YEAR};
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the last attempt; holds the wait the site asked for, if it said
+    RateLimited(Option<String>),
+    /// The part has already been solved, or isn't unlocked yet
+    WrongLevel,
+    Unrecognized,
+}
+
+impl Outcome {
+    /// Read the outcome from the HTML page returned for a submission
+    pub fn from_response(page: &str) -> Outcome {
+        if page.contains("That's the right answer") {
+            Outcome::Right
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if page.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            }
+        } else if page.contains("You gave an answer too recently") {
+            let wait = page.split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Outcome::RateLimited(wait)
+        } else if page.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unrecognized
+        }
+    }
+
+    /// Whether this outcome rules the answer out for good
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Right => f.write_str("right"),
+            Outcome::Wrong => f.write_str("wrong"),
+            Outcome::TooHigh => f.write_str("too high"),
+            Outcome::TooLow => f.write_str("too low"),
+            Outcome::RateLimited(Some(wait)) => write!(f, "rate limited, {wait} to wait"),
+            Outcome::RateLimited(None) => f.write_str("rate limited"),
+            Outcome::WrongLevel => f.write_str("wrong level"),
+            Outcome::Unrecognized => f.write_str("unrecognized response"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "right" => Outcome::Right,
+            "wrong" => Outcome::Wrong,
+            "too high" => Outcome::TooHigh,
+            "too low" => Outcome::TooLow,
+            "rate limited" => Outcome::RateLimited(None),
+            "wrong level" => Outcome::WrongLevel,
+            "unrecognized response" => Outcome::Unrecognized,
+            s => match s.strip_prefix("rate limited, ").and_then(|s| s.strip_suffix(" to wait")) {
+                Some(wait) => Outcome::RateLimited(Some(wait.to_string())),
+                None => return Err(format!("unknown outcome {s:?}")),
+            },
+        })
+    }
+}
+
+/// One answer sent to the site, and what came back
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Every answer submitted so far. They're stored one per line as `day`, `part`, `answer` and
+/// `outcome` separated by tabs, with `#` starting a comment line.
+#[derive(Debug, Default)]
+pub struct Submissions {
+    attempts: Vec<Attempt>,
+}
+
+impl Submissions {
+    /// Load a submissions file; a file that doesn't exist yet has no attempts in it
+    pub fn load(path: &Path) -> Result<Submissions, Report> {
+        let mut submissions = Submissions::default();
+        if !path.exists() {
+            return Ok(submissions)
+        }
+
+        for (line_num, line) in read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue
+            }
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            let &[day, part, answer, outcome] = fields.as_slice() else {
+                return Err(eyre!("{}:{}: expected day, part, answer and outcome separated by tabs", path.display(), line_num + 1))
+            };
+            submissions.attempts.push(Attempt {
+                day: day.parse().map_err(|_| eyre!("{}:{}: invalid day {day:?}", path.display(), line_num + 1))?,
+                part: part.parse().map_err(|_| eyre!("{}:{}: invalid part {part:?}", path.display(), line_num + 1))?,
+                answer: answer.to_string(),
+                outcome: outcome.parse().map_err(|e| eyre!("{}:{}: {e}", path.display(), line_num + 1))?,
+            });
+        }
+
+        Ok(submissions)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Report> {
+        let mut out = String::from("# day\tpart\tanswer\toutcome\n");
+        for Attempt { day, part, answer, outcome } in &self.attempts {
+            out.push_str(&format!("{day}\t{part}\t{answer}\t{outcome}\n"));
+        }
+        write(path, out)?;
+        Ok(())
+    }
+
+    pub fn attempts(&self, day: u32, part: u32) -> impl Iterator<Item = &Attempt> {
+        self.attempts.iter().filter(move |a| a.day == day && a.part == part)
+    }
+
+    /// Check `answer` against earlier attempts. It's refused if the part is already solved, if
+    /// it's been submitted and was wrong, or if it's outside the bounds set by earlier too high
+    /// and too low answers.
+    pub fn check(&self, day: u32, part: u32, answer: &str) -> Result<(), Report> {
+        let value = answer.parse::<i128>().ok();
+        for attempt in self.attempts(day, part) {
+            let earlier = attempt.answer.parse::<i128>().ok();
+            if attempt.outcome == Outcome::Right {
+                return Err(eyre!("Not submitting {answer}: day {day} part {part} was already solved with {}", attempt.answer))
+            }
+            let refused = match (&attempt.outcome, value, earlier) {
+                (outcome, _, _) if outcome.is_wrong() && attempt.answer == answer => true,
+                (Outcome::TooHigh, Some(value), Some(earlier)) => value >= earlier,
+                (Outcome::TooLow, Some(value), Some(earlier)) => value <= earlier,
+                _ => false,
+            };
+            if refused {
+                return Err(eyre!("Not submitting {answer} for day {day} part {part}: {} was {}", attempt.answer, attempt.outcome))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        self.attempts.push(attempt);
+    }
+}
+
+/// Submit `answer` for `day` and `part` unless earlier attempts rule it out, and record the outcome
+pub fn submit(fetcher: &Fetcher, submissions: &mut Submissions, day: u32, part: u32, answer: &str) -> Result<Outcome, Report> {
+    submissions.check(day, part, answer)?;
+
+    let form = format!("level={part}&answer={}", url_encode(answer));
+    let (status, page) = fetcher.request("POST", &format!("/{YEAR}/day/{day}/answer"), Some(&form))?;
+    if status != 200 {
+        return Err(eyre!("Submitting day {day} part {part} failed with HTTP status {status}"))
+    }
+
+    let outcome = Outcome::from_response(&page);
+    info!(day, part, answer, %outcome);
+    submissions.record(Attempt { day, part, answer: answer.to_string(), outcome: outcome.clone() });
+    Ok(outcome)
+}
+
+fn url_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fetch::tests::serve_once;
+
+    use super::*;
+
+    fn page(article: &str) -> String {
+        format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{article}</p></article>\n</main></body></html>\n")
+    }
+
+    fn ok_response(body: &str) -> String {
+        format!("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    fn attempt(day: u32, part: u32, answer: &str, outcome: Outcome) -> Attempt {
+        Attempt { day, part, answer: answer.to_string(), outcome }
+    }
+
+    #[test]
+    fn outcome_from_each_page() {
+        let pages = [
+            ("That's the right answer! You are one gold star closer to restoring snow operations.", Outcome::Right),
+            ("That's not the right answer. If you're stuck, make sure you're using the full input data.", Outcome::Wrong),
+            ("That's not the right answer; your answer is too high. Please wait one minute before trying again.", Outcome::TooHigh),
+            ("That's not the right answer; your answer is too low. Please wait one minute before trying again.", Outcome::TooLow),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 38s left to wait.",
+                Outcome::RateLimited(Some("38s".to_string())),
+            ),
+            ("You gave an answer too recently.", Outcome::RateLimited(None)),
+            ("You don't seem to be solving the right level. Did you already complete it?", Outcome::WrongLevel),
+            ("Something else entirely", Outcome::Unrecognized),
+        ];
+        for (article, outcome) in pages {
+            assert_eq!(Outcome::from_response(&page(article)), outcome, "{article}");
+        }
+    }
+
+    #[test]
+    fn outcome_round_trips_through_text() {
+        let outcomes = [
+            Outcome::Right, Outcome::Wrong, Outcome::TooHigh, Outcome::TooLow, Outcome::RateLimited(None),
+            Outcome::RateLimited(Some("5m 2s".to_string())), Outcome::WrongLevel, Outcome::Unrecognized,
+        ];
+        for outcome in outcomes {
+            assert_eq!(outcome.to_string().parse::<Outcome>(), Ok(outcome));
+        }
+        assert!("nearly".parse::<Outcome>().is_err());
+    }
+
+    #[test]
+    fn check_refuses_known_wrong_answers() {
+        let mut submissions = Submissions::default();
+        submissions.record(attempt(1, 1, "abc", Outcome::Wrong));
+        submissions.record(attempt(1, 1, "42", Outcome::TooHigh));
+        assert!(submissions.check(1, 1, "abc").is_err());
+        assert!(submissions.check(1, 1, "42").is_err());
+        assert!(submissions.check(1, 1, "abd").is_ok());
+        // Other parts and days aren't affected
+        assert!(submissions.check(1, 2, "abc").is_ok());
+        assert!(submissions.check(2, 1, "42").is_ok());
+    }
+
+    #[test]
+    fn check_refuses_answers_out_of_bounds() {
+        let mut submissions = Submissions::default();
+        submissions.record(attempt(5, 2, "100", Outcome::TooHigh));
+        submissions.record(attempt(5, 2, "20", Outcome::TooLow));
+        submissions.record(attempt(5, 2, "50", Outcome::RateLimited(None)));
+        assert!(submissions.check(5, 2, "100").is_err());
+        assert!(submissions.check(5, 2, "150").is_err());
+        assert!(submissions.check(5, 2, "20").is_err());
+        assert!(submissions.check(5, 2, "-3").is_err());
+        assert!(submissions.check(5, 2, "21").is_ok());
+        assert!(submissions.check(5, 2, "50").is_ok());
+        assert!(submissions.check(5, 2, "99").is_ok());
+        // Bounds say nothing about answers that aren't numbers
+        assert!(submissions.check(5, 2, "ABC").is_ok());
+    }
+
+    #[test]
+    fn check_refuses_solved_parts() {
+        let mut submissions = Submissions::default();
+        submissions.record(attempt(3, 1, "7", Outcome::Right));
+        let err = submissions.check(3, 1, "8").unwrap_err();
+        assert_eq!(err.to_string(), "Not submitting 8: day 3 part 1 was already solved with 7");
+    }
+
+    #[test]
+    fn submissions_save_and_load() {
+        let path = std::env::temp_dir().join(format!("advent23-submissions-test-{}.tsv", std::process::id()));
+        let mut submissions = Submissions::default();
+        submissions.record(attempt(1, 1, "55", Outcome::TooLow));
+        submissions.record(attempt(1, 2, "a b", Outcome::RateLimited(Some("1m".to_string()))));
+        submissions.save(&path).unwrap();
+
+        let loaded = Submissions::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let attempts: Vec<_> = loaded.attempts.iter().map(|a| (a.day, a.part, a.answer.as_str(), a.outcome.clone())).collect();
+        assert_eq!(attempts, [(1, 1, "55", Outcome::TooLow), (1, 2, "a b", Outcome::RateLimited(Some("1m".to_string())))]);
+    }
+
+    #[test]
+    fn submit_posts_the_answer_and_records_the_outcome() {
+        let (base_url, server) = serve_once(&ok_response(&page("That's not the right answer; your answer is too low.")));
+        let fetcher = Fetcher::new(&base_url, "abc").unwrap();
+        let mut submissions = Submissions::default();
+
+        let outcome = submit(&fetcher, &mut submissions, 7, 2, "a&b c").unwrap();
+        assert_eq!(outcome, Outcome::TooLow);
+        assert_eq!(submissions.attempts(7, 2).count(), 1);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with(&format!("POST /prefix/{YEAR}/day/7/answer HTTP/1.1\r\n")), "{request}");
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=a%26b%20c"), "{request}");
+    }
+
+    #[test]
+    fn submit_refuses_without_sending() {
+        // Nothing is listening here, so this would fail if it tried to connect
+        let fetcher = Fetcher::new("http://127.0.0.1:1", "abc").unwrap();
+        let mut submissions = Submissions::default();
+        submissions.record(attempt(7, 1, "10", Outcome::TooHigh));
+
+        let err = submit(&fetcher, &mut submissions, 7, 1, "12").unwrap_err();
+        assert_eq!(err.to_string(), "Not submitting 12 for day 7 part 1: 10 was too high");
+        assert_eq!(submissions.attempts(7, 1).count(), 1);
+    }
+
+    #[test]
+    fn submit_error_status() {
+        let (base_url, server) = serve_once("HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n");
+        let fetcher = Fetcher::new(&base_url, "abc").unwrap();
+        let mut submissions = Submissions::default();
+        assert!(submit(&fetcher, &mut submissions, 7, 1, "12").is_err());
+        assert_eq!(submissions.attempts(7, 1).count(), 0);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Time spent in all the spans with one name from one day, or one module for spans outside the days
+#[derive(Debug, Clone, Default)]
+pub struct SpanTotals {
+    /// Like `busy` and `max`, this leaves out spans nested inside a span of the same name, so for
+    /// a recursive routine it counts the outermost calls
+    pub calls: u64,
+    /// Time inside the spans, not counting time inside a span of the same name nested in one of
+    /// them, so that recursive routines aren't counted more than once
+    pub busy: Duration,
+    pub max: Duration,
+}
+
+static TOTALS: Mutex<BTreeMap<(String, &'static str), SpanTotals>> = Mutex::new(BTreeMap::new());
+
+/// Take the totals gathered so far, keyed by day (or module) and span name
+pub fn take() -> BTreeMap<(String, &'static str), SpanTotals> {
+    std::mem::take(&mut *TOTALS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Print the totals gathered so far as a table on stderr, busiest first
+pub fn print_report() {
+    let mut totals: Vec<_> = take().into_iter().collect();
+    if totals.is_empty() {
+        return
+    }
+    totals.sort_by_key(|(_, t)| std::cmp::Reverse(t.busy));
+    eprintln!("{:<20}  {:<10}  {:>8}  {:>12}  {:>12}  {:>12}", "Day", "Span", "Calls", "Busy", "Mean", "Max");
+    for ((label, name), t) in totals {
+        let mean = t.busy.div_f64(t.calls.max(1) as f64);
+        eprintln!("{:<20}  {:<10}  {:>8}  {:>12}  {:>12}  {:>12}",
+            label, name, t.calls, format!("{:.3?}", t.busy), format!("{mean:.3?}"), format!("{:.3?}", t.max));
+    }
+}
+
+/// When a span was entered, what it's totalled under, and whether a span of the same name
+/// encloses it
+struct Entered {
+    at: Option<Instant>,
+    label: String,
+    nested: bool,
+}
+
+/// Finds a span's `day` field
+#[derive(Default)]
+struct DayVisitor(Option<String>);
+
+impl Visit for DayVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "day" {
+            self.0 = Some(format!("day {value:?}"));
+        }
+    }
+}
+
+/// A layer that adds up how long every span is entered for, for `print_report`
+pub struct TimingLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for TimingLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let name = span.name();
+        let nested = span.scope().skip(1).any(|ancestor| ancestor.name() == name);
+
+        let mut day = DayVisitor::default();
+        attrs.record(&mut day);
+        let target = span.metadata().target();
+        let label = day.0
+            .or_else(|| target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::day")).map(|day| format!("day {day}")))
+            .unwrap_or_else(|| target.to_string());
+        span.extensions_mut().insert(Entered { at: None, label, nested });
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(entered) = extensions.get_mut::<Entered>() {
+            entered.at = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        let Some(entered) = extensions.get_mut::<Entered>() else { return };
+        let Some(elapsed) = entered.at.take().map(|at| at.elapsed()) else { return };
+
+        let mut totals = TOTALS.lock().unwrap_or_else(|e| e.into_inner());
+        let totals = totals.entry((entered.label.clone(), span.name())).or_default();
+        if !entered.nested {
+            totals.calls += 1;
+            totals.busy += elapsed;
+            totals.max = totals.max.max(elapsed);
+        }
+    }
+}
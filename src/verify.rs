@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Report;
+use tracing::warn;
+
+use crate::answer::Answers;
+
+/// Known-good answers, keyed by day, part and input file. They're stored one per line as
+/// `day`, `part`, `input` and `answer` separated by tabs, with `#` starting a comment line.
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    entries: BTreeMap<(u32, u32, PathBuf), String>,
+}
+
+impl ExpectedAnswers {
+    /// Load an answers file; a file that doesn't exist yet has no answers in it
+    pub fn load(path: &Path) -> Result<ExpectedAnswers, Report> {
+        let mut expected = ExpectedAnswers::default();
+        if !path.exists() {
+            return Ok(expected)
+        }
+
+        for (line_num, line) in read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue
+            }
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            let &[day, part, input, answer] = fields.as_slice() else {
+                return Err(eyre!("{}:{}: expected day, part, input and answer separated by tabs", path.display(), line_num + 1))
+            };
+            let day = day.parse().map_err(|_| eyre!("{}:{}: invalid day {day:?}", path.display(), line_num + 1))?;
+            let part = part.parse().map_err(|_| eyre!("{}:{}: invalid part {part:?}", path.display(), line_num + 1))?;
+            expected.entries.insert((day, part, PathBuf::from(input)), answer.to_string());
+        }
+
+        Ok(expected)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Report> {
+        let mut out = String::from("# day\tpart\tinput\tanswer\n");
+        for ((day, part, input), answer) in &self.entries {
+            out.push_str(&format!("{day}\t{part}\t{}\t{answer}\n", input.display()));
+        }
+        write(path, out)?;
+        Ok(())
+    }
+
+    /// Lock in every answer in `answers` as the expected answer for `day` on `input`
+    pub fn record(&mut self, day: u32, input: &Path, answers: &Answers) {
+        for (part, answer) in answers.iter() {
+            self.entries.insert((day, part, input.to_path_buf()), answer.to_string());
+        }
+    }
+
+    /// The inputs that have expected answers, with the parts expected for each
+    fn by_input(&self) -> BTreeMap<(u32, &Path), Vec<(u32, &str)>> {
+        let mut grouped: BTreeMap<(u32, &Path), Vec<(u32, &str)>> = BTreeMap::new();
+        for ((day, part, input), answer) in &self.entries {
+            grouped.entry((*day, input.as_path())).or_default().push((*part, answer.as_str()));
+        }
+        grouped
+    }
+}
+
+/// Run every solver that has expected answers and report pass, fail or missing for each entry.
+/// Returns the number of mismatches, counting a solver error, a missing answer or a missing input
+/// as a mismatch for every part it affects.
+pub fn verify(expected: &ExpectedAnswers, part: Option<u32>, raw: bool) -> usize {
+    let mut mismatches = 0;
+
+    for ((day, input), parts) in expected.by_input() {
+        let parts: Vec<_> = parts.into_iter().filter(|(p, _)| part.is_none_or(|want| *p == want)).collect();
+        if parts.is_empty() {
+            continue
+        }
+
+        let answers = match crate::solver::find(day) {
+            None => Err(eyre!("no such puzzle")),
+            Some(_) if !input.exists() => Err(eyre!("{} not found", input.display())),
+            Some(solver) => Ok(crate::input::read_input(input, raw).and_then(|text| solver.solve(text, part))),
+        };
+
+        if let Ok(Ok(answers)) = &answers {
+            for problem in crate::results::check(day, answers) {
+                warn!("{problem}");
+            }
+        }
+
+        for (p, want) in parts {
+            let status = match &answers {
+                Ok(Ok(answers)) => match answers.iter().find(|(ap, _)| *ap == p) {
+                    Some((_, got)) if got.to_string() == want => "pass".to_string(),
+                    Some((_, got)) => {
+                        mismatches += 1;
+                        format!("FAIL: expected {want}, got {got}")
+                    }
+                    None => {
+                        mismatches += 1;
+                        "missing: no answer".to_string()
+                    }
+                },
+                Ok(Err(e)) => {
+                    mismatches += 1;
+                    format!("FAIL: {e}")
+                }
+                Err(e) => {
+                    mismatches += 1;
+                    format!("missing: {e}")
+                }
+            };
+            println!("{day:>3}  {p:>4}  {}  {status}", input.display());
+        }
+    }
+
+    mismatches
+}
@@ -0,0 +1,50 @@
+use std::fs::metadata;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Report;
+use tracing::{debug, info, warn};
+
+use crate::answer::Answers;
+use crate::input::read_input;
+use crate::solver::Solver;
+
+/// Solve `path` with `solver`, then poll its modification time every `interval` and solve it again
+/// each time it changes, showing each answer next to the one from the run before. Failures are
+/// reported and the watch carries on; it only stops when interrupted.
+pub fn watch(solver: &dyn Solver, path: &Path, part: Option<u32>, raw: bool, interval: Duration) -> Result<(), Report> {
+    let mut last_modified: Option<SystemTime> = None;
+    let mut previous: Option<Answers> = None;
+
+    loop {
+        let modified = match metadata(path).and_then(|m| m.modified()) {
+            Ok(modified) => Some(modified),
+            Err(e) => {
+                debug!("{path:?}: {e}");
+                None
+            }
+        };
+        if modified.is_some() && modified != last_modified {
+            last_modified = modified;
+            match read_input(path, raw).and_then(|input| solver.solve(input, part)) {
+                Ok(answers) => {
+                    for problem in crate::results::check(solver.day(), &answers) {
+                        warn!("{problem}");
+                    }
+                    for (p, answer) in answers.iter() {
+                        let was = previous.as_ref().and_then(|prev| prev.iter().find(|(pp, _)| *pp == p));
+                        match was {
+                            Some((_, was)) => println!("Day {} part {p}: {answer}  (was {was})", solver.day()),
+                            None => println!("Day {} part {p}: {answer}", solver.day()),
+                        }
+                    }
+                    previous = Some(answers);
+                }
+                Err(e) => warn!("{}: {e}", path.display()),
+            }
+            info!("watching {} for changes", path.display());
+        }
+        sleep(interval);
+    }
+}